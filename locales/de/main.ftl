@@ -0,0 +1,315 @@
+# Top navigation
+nav-chats = Chats
+nav-settings = Einstellungen
+nav-models = Modelle
+nav-library = Bibliothek
+nav-prompts = Vorlagen
+nav-arena = Arena
+nav-statistics = Statistik
+nav-diagnostics = Diagnose
+
+# Server health
+health-checking = ○ Wird geprüft...
+health-connected = ● Verbunden ({ $version })
+health-disconnected = ● Getrennt
+health-failed-checks = { $failures ->
+    [one] 1 fehlgeschlagene Prüfung: { $error }
+   *[other] { $failures } fehlgeschlagene Prüfungen: { $error }
+}
+
+# Sidebar
+sidebar-new-chat = Neuer Chat
+sidebar-search = Chats durchsuchen
+sidebar-chat-name = Chatname
+
+# Chat
+chat-prompt = Eingabe: { $prompt }
+chat-prompts = Vorlagen
+chat-input-placeholder = Eingabe tippen, oder / für Vorlagen...
+chat-send = Senden
+chat-stop = Stopp
+chat-cancel = Abbrechen
+chat-retry = Wiederholen
+chat-queued = Der Server ist nicht erreichbar. Die Eingabe wird gesendet, sobald er wieder verbunden ist.
+chat-offline = Der Server ist offline. Senden ist deaktiviert, bis er wieder verbunden ist.
+context-tokens = Kontext: { $approx }{ $used } Tokens
+context-usage = { $approx }{ $used } / { $limit } Tokens ({ $percent } %)
+context-drop-oldest = Ältesten Wechsel entfernen
+context-summarize = Ältere Wechsel zusammenfassen
+context-continue = In neuem Chat fortsetzen
+context-summarizing = Unterhaltung wird zusammengefasst...
+context-num-ctx = num_ctx
+chat-continued = { $name } (fortgesetzt)
+chat-summary-failed = Der Chat konnte nicht zusammengefasst werden: { $error }
+chat-not-found = Kein Chat hat die ID { $id }
+error-message = Fehler: { $error }
+chat-stats = { $tokens } Tokens · { $speed } Tok/s · erstes Token { $first } · Laden { $load }
+
+# Settings
+settings-language = Sprache
+settings-servers = Server
+settings-select-model = Modell auswählen
+settings-refresh-models = Modelle aktualisieren
+settings-send-with = Senden mit
+settings-send-enter = Eingabe sendet, Umschalt+Eingabe fügt eine Zeile ein
+settings-send-shift-enter = Umschalt+Eingabe sendet, Eingabe fügt eine Zeile ein
+settings-auto-titles = Chattitel automatisch erzeugen
+settings-title-model = Modell für Titel
+settings-same-as-chat = Wie der Chat
+settings-download-model = Modell herunterladen
+settings-browse-library = Bibliothek durchsuchen
+settings-model-name = Modellname
+settings-download = Herunterladen
+settings-parallel-downloads = Gleichzeitige Downloads
+
+# Keyboard shortcuts
+shortcuts-title = Tastenkürzel
+shortcuts-hint = Zum Ändern settings/keymap.json bearbeiten und neu starten.
+shortcuts-invalid = keymap.json: { $keys } für { $action }: { $error }
+shortcuts-unknown-modifier = unbekannte Sondertaste { $modifier }
+shortcuts-missing-key = Taste fehlt
+action-new-chat = Neuer Chat
+action-next-chat = Nächster Chat
+action-previous-chat = Vorheriger Chat
+action-focus-input = Eingabefeld fokussieren
+action-stop-generation = Generierung stoppen
+action-regenerate = Letzte Antwort neu erzeugen
+action-open-settings = Einstellungen öffnen
+action-search = Chats durchsuchen
+action-command-palette = Befehlspalette
+action-toggle-sidebar = Seitenleiste ein- oder ausblenden
+action-zoom-in = Vergrößern
+action-zoom-out = Verkleinern
+action-reset-zoom = Zoom zurücksetzen
+
+# Automation API
+api-title = Automatisierungs-API
+api-enable = Lokalen Programmen die API erlauben
+api-port = Port
+api-port-invalid = Der Port muss eine Zahl von 1024 bis 65535 sein.
+api-apply = Übernehmen
+api-token-missing = settings/api_token konnte nicht geschrieben werden
+api-listening = Erreichbar unter http://127.0.0.1:{ $port }/api/v1. Das Token aus settings/api_token als "Authorization: Bearer <token>" mitsenden.
+api-listen-failed = Die Automatisierungs-API konnte Port { $port } nicht öffnen: { $error }
+
+# Appearance
+appearance-title = Darstellung
+appearance-light = Hell
+appearance-dark = Dunkel
+appearance-follow-system = Hellen oder dunklen Modus des Systems übernehmen
+appearance-edit-theme = Als neues Design bearbeiten
+appearance-reload-themes = Designs neu laden
+appearance-font = Schrift
+appearance-font-default = Standard
+appearance-code-font = Code-Schrift
+appearance-size = Größe
+appearance-apply = Übernehmen
+appearance-fonts-hint = Schriften müssen installiert sein. Schrift und Größe der Oberfläche gelten nach einem Neustart. Design-Dateien gehören nach { $dir }.
+appearance-zoom = Zoom
+appearance-reset = Zurücksetzen
+appearance-code-font-default = Monospace
+appearance-theme-saved = Design gespeichert unter { $path }
+appearance-theme-save-failed = Das Design konnte nicht gespeichert werden: { $error }
+appearance-size-invalid = Die Textgröße muss eine Zahl sein.
+appearance-restart-needed = Schrift und Textgröße der Oberfläche ändern sich nach einem Neustart.
+appearance-custom-theme = { $theme } (angepasst)
+
+# Theme editor
+theme-name = Name
+theme-name-placeholder = Name des Designs
+theme-background = Hintergrund
+theme-text = Text
+theme-primary = Primär
+theme-success = Erfolg
+theme-danger = Gefahr
+theme-save = Design speichern
+theme-cancel = Abbrechen
+theme-color-placeholder = #rrggbb
+theme-name-missing = das Design braucht einen Namen
+theme-name-builtin = { $name } ist der Name eines eingebauten Designs
+theme-color-invalid = { $color }: { $value } ist keine Hex-Farbe
+
+# Command palette
+palette-placeholder = Befehl, Chat oder Modell eingeben...
+palette-no-matches = Keine Treffer
+palette-hint = ↑↓ zum Auswählen, Eingabe zum Ausführen, Esc zum Schließen
+palette-close = Schließen
+palette-chat = Chat: { $name }
+palette-model = Modell: { $name }
+
+# Prompt template form
+prompt-form-send = Senden
+prompt-form-insert = Einfügen
+prompt-form-cancel = Abbrechen
+
+# Downloads
+download-speed = { $speed }/s
+download-speed-eta = { $speed }/s, noch { $eta }
+download-downloading = Herunterladen
+download-retry = Wiederholen
+download-starting = Download wird gestartet...
+download-queued = In der Warteschlange
+download-retrying = Neuer Versuch in { $seconds } s (Versuch { $attempt }/{ $attempts }): { $error }
+download-finished = { $model } heruntergeladen
+progress-cancel = Abbrechen
+
+# Servers
+server-active = { $name } (aktiv)
+server-edit = Bearbeiten
+server-delete = Löschen
+server-testing = Wird getestet...
+server-connected = Verbunden mit { $version }
+server-connection-failed = Verbindung fehlgeschlagen: { $error }
+server-name = Name
+server-url-placeholder = http://localhost:11434
+server-url-empty = Serveradresse eingeben, z. B. http://localhost:11434
+server-url-scheme = Die Adresse muss mit http:// oder https:// beginnen
+server-url-invalid = Ungültige Adresse: { $error }
+server-url-no-host = Die Adresse braucht einen Hostnamen
+server-url-query = Die Adresse darf kein ? oder # enthalten
+server-test = Verbindung testen
+server-protocol = Protokoll
+server-token = Token
+server-username = Benutzername
+server-password = Passwort
+server-headers = Zusätzliche Header, ein "Name: Wert" pro Zeile
+server-ca-cert = CA-Zertifikat (PEM-Datei, optional)
+server-client-cert = Client-Zertifikat + Schlüssel (PEM-Datei, optional)
+server-timeout = Zeitlimit (s)
+server-accept-invalid-certs = Ungültige Zertifikate akzeptieren
+server-secrets-hint = Passwörter, Tokens und Header werden in settings/secrets.json gespeichert.
+server-save = Server speichern
+server-new = Neuer Server
+server-local = Lokal
+server-command-line = Kommandozeile
+server-header-name-invalid = Ungültiger Headername: { $name }
+server-header-value-invalid = Ungültiger Wert für Header { $name }
+server-credentials-invalid = Die Zugangsdaten enthalten ungültige Zeichen
+server-file-unreadable = { $path } konnte nicht gelesen werden: { $error }
+server-ca-cert-invalid = Ungültiges CA-Zertifikat: { $error }
+server-client-cert-invalid = Ungültiges Client-Zertifikat: { $error }
+auth-none = Keine Anmeldung
+auth-bearer = Bearer-Token
+auth-basic = Basic-Anmeldung
+backend-openai = OpenAI-kompatibel
+
+# Model manager
+models-filter = Modelle filtern
+models-sort-by = Sortieren nach
+models-sort-name = Name
+models-sort-size = Größe
+models-sort-modified = Zuletzt geändert
+models-sort-family = Familie
+models-new = Neues Modell
+models-name = Name
+models-size = Größe
+models-family = Familie
+models-parameters = Parameter
+models-quantization = Quantisierung
+models-digest = Prüfsumme
+models-modified = Geändert
+models-confirm = Bestätigen
+models-delete = Löschen
+models-details = Details
+models-loaded = Geladene Modelle
+models-refresh = Aktualisieren
+models-vram = VRAM { $vram } / { $size }
+models-until = bis { $time }
+models-unload = Entladen
+models-edit = Bearbeiten
+models-new-name = Neuer Name
+models-copy = Kopieren
+models-rename = Umbenennen
+models-modelfile = Modelfile
+models-template = Vorlage
+models-license = Lizenz
+
+# Model editor
+model-editor-name = Name
+model-editor-name-placeholder = mein-modell
+model-editor-from = FROM
+model-editor-base-model = Basismodell
+model-editor-system = SYSTEM
+model-editor-template = TEMPLATE
+model-editor-parameters = PARAMETER (ein "Name Wert" pro Zeile)
+model-editor-create = Erstellen
+model-editor-creating = Erstellen
+model-editor-starting = Wird gestartet...
+model-editor-modelfile = Modelfile
+model-editor-name-required = Name: erforderlich
+model-editor-name-spaces = Name: darf keine Leerzeichen enthalten
+
+# Modelfile checks
+modelfile-unterminated-block = Zeile { $line }: nicht abgeschlossener """-Block
+modelfile-parameter-no-value = Zeile { $line }: PARAMETER braucht einen Wert
+modelfile-unknown-instruction = Zeile { $line }: unbekannte Anweisung { $instruction }
+modelfile-from-required = FROM: ein Basismodell ist erforderlich
+modelfile-triple-quotes = TEMPLATE/SYSTEM darf kein """ enthalten
+modelfile-parameter-invalid = PARAMETER { $name }: { $error }
+modelfile-template-invalid = TEMPLATE: { $error }
+modelfile-missing-value = Wert fehlt
+modelfile-expected-integer = Ganzzahl erwartet, { $value } erhalten
+modelfile-expected-number = Zahl erwartet, { $value } erhalten
+modelfile-expected-bool = true oder false erwartet, { $value } erhalten
+modelfile-unclosed-action = nicht geschlossenes {"{{"}
+modelfile-unmatched-end = {"{{"} end {"}}"} ohne passenden Block
+modelfile-unmatched-close = {"}}"} ohne passendes {"{{"}
+modelfile-missing-end = { $count } Block/Blöcke ohne {"{{"} end {"}}"}
+
+# Prompt library
+prompts-title = Vorlagen
+prompts-edit = Bearbeiten
+prompts-delete = Löschen
+prompts-name = Name
+prompts-command = /befehl
+prompts-variables-hint = {"{{"}name{"}}"} steht für Werte, die beim Verwenden der Vorlage abgefragt werden.
+prompts-save = Vorlage speichern
+prompts-new = Neue Vorlage
+prompts-path = Pfad zu einer .json-Datei mit Vorlagen
+prompts-import = Importieren
+prompts-export = Exportieren
+prompts-saved = { $name } gespeichert
+prompts-imported = { $count } Vorlagen importiert
+prompts-exported = { $count } Vorlagen exportiert
+
+# Arena
+arena-placeholder = Eingabe für alle ausgewählten Modelle...
+arena-compare = Vergleichen
+arena-generating = Wird erzeugt...
+arena-stats = { $speed } Tok/s · { $tokens } Tokens · { $chars } Zeichen · erstes Token { $first }
+arena-winner = ★ Gewinner
+arena-vote = Abstimmen
+arena-select = Zwei bis { $max } Modelle auswählen.
+arena-selected = { $count } Modelle ausgewählt.
+arena-continue = Mit dem Gewinner fortsetzen
+arena-chat-name = Arena: { $model }
+
+# Model library
+library-search = Modelle suchen
+library-free = { $size } frei für Modelle
+library-free-unknown = Freier Speicher unbekannt (entfernter Server)
+library-tag-no-space = { $tag } ({ $size }, nicht genug Speicher)
+library-path = Pfad zu library.json
+library-import = Katalog importieren
+library-catalog-date = Katalog vom { $date }
+
+# Statistics
+statistics-per-model = Pro Modell
+statistics-per-chat = Pro Chat
+statistics-model = Modell
+statistics-chat = Chat
+statistics-responses = Antworten
+statistics-tokens = Tokens
+statistics-tokens-per-second = Tok/s
+statistics-first-token = Ø erstes Token
+statistics-load = Ø Laden
+
+# Diagnostics
+diagnostics-copy = Diagnosepaket kopieren
+diagnostics-copied = Diagnosepaket in die Zwischenablage kopiert
+diagnostics-redacted = Eingaben, Tokens, Passwörter und Header-Werte werden weggelassen.
+diagnostics-logs = Protokolldateien: { $dir }
+diagnostics-servers = Server
+diagnostics-events = Letzte Ereignisse (UTC, neueste zuerst)
+diagnostics-not-checked = noch nicht geprüft
+diagnostics-unreachable = nicht erreichbar ({ $failures } fehlgeschlagene Prüfungen): { $error }
@@ -0,0 +1,315 @@
+# Top navigation
+nav-chats = Chats
+nav-settings = Settings
+nav-models = Models
+nav-library = Library
+nav-prompts = Prompts
+nav-arena = Arena
+nav-statistics = Statistics
+nav-diagnostics = Diagnostics
+
+# Server health
+health-checking = ○ Checking...
+health-connected = ● Connected ({ $version })
+health-disconnected = ● Disconnected
+health-failed-checks = { $failures ->
+    [one] 1 failed check: { $error }
+   *[other] { $failures } failed checks: { $error }
+}
+
+# Sidebar
+sidebar-new-chat = New Chat
+sidebar-search = Search chats
+sidebar-chat-name = Chat Name
+
+# Chat
+chat-prompt = Prompt: { $prompt }
+chat-prompts = Prompts
+chat-input-placeholder = Type your prompt, or / for templates...
+chat-send = Send
+chat-stop = Stop
+chat-cancel = Cancel
+chat-retry = Retry
+chat-queued = The server is unreachable. Your prompt will be sent when it reconnects.
+chat-offline = The server is offline. Sending is disabled until it reconnects.
+context-tokens = Context: { $approx }{ $used } tokens
+context-usage = { $approx }{ $used } / { $limit } tokens ({ $percent }%)
+context-drop-oldest = Drop oldest turn
+context-summarize = Summarize older turns
+context-continue = Continue in new chat
+context-summarizing = Summarizing conversation...
+context-num-ctx = num_ctx
+chat-continued = { $name } (continued)
+chat-summary-failed = Could not summarize the chat: { $error }
+chat-not-found = No chat has the id { $id }
+error-message = Error: { $error }
+chat-stats = { $tokens } tokens · { $speed } tok/s · first token { $first } · load { $load }
+
+# Settings
+settings-language = Language
+settings-servers = Servers
+settings-select-model = Select Model
+settings-refresh-models = Refresh Models
+settings-send-with = Send with
+settings-send-enter = Enter sends, Shift+Enter adds a line
+settings-send-shift-enter = Shift+Enter sends, Enter adds a line
+settings-auto-titles = Generate chat titles automatically
+settings-title-model = Title model
+settings-same-as-chat = Same as chat
+settings-download-model = Download Model
+settings-browse-library = Browse library
+settings-model-name = Model name
+settings-download = Download
+settings-parallel-downloads = Parallel downloads
+
+# Keyboard shortcuts
+shortcuts-title = Keyboard shortcuts
+shortcuts-hint = Edit settings/keymap.json and restart to change them.
+shortcuts-invalid = keymap.json: { $keys } for { $action }: { $error }
+shortcuts-unknown-modifier = unknown modifier { $modifier }
+shortcuts-missing-key = missing key
+action-new-chat = New chat
+action-next-chat = Next chat
+action-previous-chat = Previous chat
+action-focus-input = Focus prompt input
+action-stop-generation = Stop generation
+action-regenerate = Regenerate last response
+action-open-settings = Open settings
+action-search = Search chats
+action-command-palette = Command palette
+action-toggle-sidebar = Show or hide the sidebar
+action-zoom-in = Zoom in
+action-zoom-out = Zoom out
+action-reset-zoom = Reset zoom
+
+# Automation API
+api-title = Automation API
+api-enable = Let local tools use the API
+api-port = Port
+api-port-invalid = The port must be a number from 1024 to 65535.
+api-apply = Apply
+api-token-missing = Could not write settings/api_token
+api-listening = Listening on http://127.0.0.1:{ $port }/api/v1. Send the token in settings/api_token as "Authorization: Bearer <token>".
+api-listen-failed = Automation API could not listen on port { $port }: { $error }
+
+# Appearance
+appearance-title = Appearance
+appearance-light = Light
+appearance-dark = Dark
+appearance-follow-system = Follow the system's light or dark mode
+appearance-edit-theme = Edit as new theme
+appearance-reload-themes = Reload themes
+appearance-font = Font
+appearance-font-default = Default
+appearance-code-font = Code font
+appearance-size = Size
+appearance-apply = Apply
+appearance-fonts-hint = Fonts must be installed. The interface font and size apply after a restart. Theme files go in { $dir }.
+appearance-zoom = Zoom
+appearance-reset = Reset
+appearance-code-font-default = Monospace
+appearance-theme-saved = Saved theme to { $path }
+appearance-theme-save-failed = Could not save the theme: { $error }
+appearance-size-invalid = The text size must be a number.
+appearance-restart-needed = The interface font and text size change when the app restarts.
+appearance-custom-theme = { $theme } (custom)
+
+# Theme editor
+theme-name = Name
+theme-name-placeholder = Theme name
+theme-background = Background
+theme-text = Text
+theme-primary = Primary
+theme-success = Success
+theme-danger = Danger
+theme-save = Save theme
+theme-cancel = Cancel
+theme-color-placeholder = #rrggbb
+theme-name-missing = the theme needs a name
+theme-name-builtin = { $name } is the name of a built-in theme
+theme-color-invalid = { $color }: { $value } is not a hex color
+
+# Command palette
+palette-placeholder = Type a command, chat or model...
+palette-no-matches = No matches
+palette-hint = ↑↓ to choose, Enter to run, Esc to close
+palette-close = Close
+palette-chat = Chat: { $name }
+palette-model = Model: { $name }
+
+# Prompt template form
+prompt-form-send = Send
+prompt-form-insert = Insert
+prompt-form-cancel = Cancel
+
+# Downloads
+download-speed = { $speed }/s
+download-speed-eta = { $speed }/s, { $eta } left
+download-downloading = Downloading
+download-retry = Retry
+download-starting = Starting download...
+download-queued = Queued
+download-retrying = Retrying in { $seconds }s (attempt { $attempt }/{ $attempts }): { $error }
+download-finished = Downloaded { $model }
+progress-cancel = Cancel
+
+# Servers
+server-active = { $name } (active)
+server-edit = Edit
+server-delete = Delete
+server-testing = Testing...
+server-connected = Connected to { $version }
+server-connection-failed = Connection failed: { $error }
+server-name = Name
+server-url-placeholder = http://localhost:11434
+server-url-empty = Enter the server address, e.g. http://localhost:11434
+server-url-scheme = The address must start with http:// or https://
+server-url-invalid = Invalid address: { $error }
+server-url-no-host = The address needs a host name
+server-url-query = The address must not contain ? or #
+server-test = Test connection
+server-protocol = Protocol
+server-token = Token
+server-username = User name
+server-password = Password
+server-headers = Extra headers, one "Name: value" per line
+server-ca-cert = CA certificate (PEM file, optional)
+server-client-cert = Client certificate + key (PEM file, optional)
+server-timeout = Timeout (s)
+server-accept-invalid-certs = Accept invalid certificates
+server-secrets-hint = Passwords, tokens and headers are stored in settings/secrets.json.
+server-save = Save server
+server-new = New server
+server-local = Local
+server-command-line = Command line
+server-header-name-invalid = Invalid header name: { $name }
+server-header-value-invalid = Invalid value for header { $name }
+server-credentials-invalid = Credentials contain invalid characters
+server-file-unreadable = Could not read { $path }: { $error }
+server-ca-cert-invalid = Invalid CA certificate: { $error }
+server-client-cert-invalid = Invalid client certificate: { $error }
+auth-none = No authentication
+auth-bearer = Bearer token
+auth-basic = Basic auth
+backend-openai = OpenAI-compatible
+
+# Model manager
+models-filter = Filter models
+models-sort-by = Sort by
+models-sort-name = Name
+models-sort-size = Size
+models-sort-modified = Last modified
+models-sort-family = Family
+models-new = New Model
+models-name = Name
+models-size = Size
+models-family = Family
+models-parameters = Parameters
+models-quantization = Quantization
+models-digest = Digest
+models-modified = Modified
+models-confirm = Confirm
+models-delete = Delete
+models-details = Details
+models-loaded = Loaded Models
+models-refresh = Refresh
+models-vram = VRAM { $vram } / { $size }
+models-until = until { $time }
+models-unload = Unload
+models-edit = Edit
+models-new-name = New name
+models-copy = Copy
+models-rename = Rename
+models-modelfile = Modelfile
+models-template = Template
+models-license = License
+
+# Model editor
+model-editor-name = Name
+model-editor-name-placeholder = my-model
+model-editor-from = FROM
+model-editor-base-model = Base model
+model-editor-system = SYSTEM
+model-editor-template = TEMPLATE
+model-editor-parameters = PARAMETER (one "name value" per line)
+model-editor-create = Create
+model-editor-creating = Creating
+model-editor-starting = Starting...
+model-editor-modelfile = Modelfile
+model-editor-name-required = Name: required
+model-editor-name-spaces = Name: must not contain spaces
+
+# Modelfile checks
+modelfile-unterminated-block = Line { $line }: unterminated """ block
+modelfile-parameter-no-value = Line { $line }: PARAMETER needs a value
+modelfile-unknown-instruction = Line { $line }: unknown instruction { $instruction }
+modelfile-from-required = FROM: a base model is required
+modelfile-triple-quotes = TEMPLATE/SYSTEM must not contain """
+modelfile-parameter-invalid = PARAMETER { $name }: { $error }
+modelfile-template-invalid = TEMPLATE: { $error }
+modelfile-missing-value = missing value
+modelfile-expected-integer = expected an integer, got { $value }
+modelfile-expected-number = expected a number, got { $value }
+modelfile-expected-bool = expected true or false, got { $value }
+modelfile-unclosed-action = unclosed {"{{"}
+modelfile-unmatched-end = {"{{"} end {"}}"} without a matching block
+modelfile-unmatched-close = {"}}"} without a matching {"{{"}
+modelfile-missing-end = { $count } block(s) missing {"{{"} end {"}}"}
+
+# Prompt library
+prompts-title = Prompt library
+prompts-edit = Edit
+prompts-delete = Delete
+prompts-name = Name
+prompts-command = /command
+prompts-variables-hint = Use {"{{"}name{"}}"} for values asked for when the template is used.
+prompts-save = Save template
+prompts-new = New template
+prompts-path = Path to a prompts .json file
+prompts-import = Import
+prompts-export = Export
+prompts-saved = Saved { $name }
+prompts-imported = Imported { $count } templates
+prompts-exported = Exported { $count } templates
+
+# Arena
+arena-placeholder = Prompt sent to every selected model...
+arena-compare = Compare
+arena-generating = Generating...
+arena-stats = { $speed } tok/s · { $tokens } tokens · { $chars } chars · first token { $first }
+arena-winner = ★ Winner
+arena-vote = Vote
+arena-select = Select two to { $max } models.
+arena-selected = { $count } models selected.
+arena-continue = Continue with the winner
+arena-chat-name = Arena: { $model }
+
+# Model library
+library-search = Search models
+library-free = { $size } free for models
+library-free-unknown = Free space unknown (remote server)
+library-tag-no-space = { $tag } ({ $size }, not enough space)
+library-path = Path to library.json
+library-import = Import catalog
+library-catalog-date = Catalog from { $date }
+
+# Statistics
+statistics-per-model = Per Model
+statistics-per-chat = Per Chat
+statistics-model = Model
+statistics-chat = Chat
+statistics-responses = Responses
+statistics-tokens = Tokens
+statistics-tokens-per-second = Tok/s
+statistics-first-token = Avg first token
+statistics-load = Avg load
+
+# Diagnostics
+diagnostics-copy = Copy diagnostics bundle
+diagnostics-copied = Diagnostics bundle copied to the clipboard
+diagnostics-redacted = Prompts, tokens, passwords and header values are left out.
+diagnostics-logs = Log files: { $dir }
+diagnostics-servers = Servers
+diagnostics-events = Recent events (UTC, newest first)
+diagnostics-not-checked = not checked yet
+diagnostics-unreachable = unreachable ({ $failures } failed checks): { $error }
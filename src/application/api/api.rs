@@ -0,0 +1,318 @@
+use crate::application::i18n::i18n::tr_args;
+use iced::futures::channel::mpsc;
+use iced::futures::{SinkExt, Stream, StreamExt};
+use serde_json::{json, Value};
+use std::fs;
+use std::time::Duration;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, Take};
+use tokio::net::{TcpListener, TcpStream};
+use uuid::Uuid;
+
+const TOKEN_PATH: &str = "./settings/api_token";
+
+/// Largest request body the API accepts.
+const MAX_BODY: usize = 1024 * 1024;
+
+/// Longest request or header line, and largest request head, the API accepts.
+const MAX_LINE: u64 = 8 * 1024;
+const MAX_HEAD: u64 = 32 * 1024;
+
+/// Time a client gets to send its whole request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub const DEFAULT_PORT: u16 = 11435;
+
+/// What the window sends back for a request: a single JSON body, an error, or
+/// the lines of a streamed reply until `End`.
+#[derive(Debug, Clone)]
+pub enum ApiResponse {
+    Json(Value),
+    Error(u16, String),
+    Line(Value),
+    End,
+}
+
+pub type ApiReply = mpsc::UnboundedSender<ApiResponse>;
+
+/// A request for the window to handle. Every variant carries the channel its
+/// response goes to.
+#[derive(Debug, Clone)]
+pub enum ApiRequest {
+    ListChats(ApiReply),
+    CreateChat {
+        name: Option<String>,
+        model: Option<String>,
+        reply: ApiReply,
+    },
+    /// `chat` is a UUID or a chat name.
+    SendPrompt {
+        chat: String,
+        prompt: String,
+        reply: ApiReply,
+    },
+    /// The listener could not start.
+    Failed(String),
+}
+
+/// Reads the token clients must send as `Authorization: Bearer <token>`,
+/// creating it on first use. The file is readable only by the current user.
+pub fn load_or_create_token() -> std::io::Result<String> {
+    if let Ok(token) = fs::read_to_string(TOKEN_PATH) {
+        if !token.trim().is_empty() {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(TOKEN_PATH, fs::Permissions::from_mode(0o600))?;
+            }
+            return Ok(token.trim().to_string());
+        }
+    }
+    let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+    fs::create_dir_all("./settings")?;
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(TOKEN_PATH)?;
+    std::io::Write::write_all(&mut file, token.as_bytes())?;
+    Ok(token)
+}
+
+/// Listens on `127.0.0.1:port` and yields each authenticated request.
+pub fn serve(port: u16, token: String) -> impl Stream<Item = ApiRequest> {
+    iced::stream::channel(100, move |mut output| async move {
+        let listener = match TcpListener::bind(("127.0.0.1", port)).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                let _ = output
+                    .send(ApiRequest::Failed(tr_args(
+                        "api-listen-failed",
+                        &[("port", port.into()), ("error", e.to_string().into())],
+                    )))
+                    .await;
+                // Stay idle instead of ending, which would restart the subscription.
+                return iced::futures::future::pending().await;
+            }
+        };
+        while let Ok((stream, _)) = listener.accept().await {
+            tokio::spawn(handle_connection(stream, token.clone(), output.clone()));
+        }
+    })
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+/// Reads one line of the request head, failing once it grows past `MAX_LINE`
+/// or the head past `MAX_HEAD`.
+async fn read_head_line<R: AsyncBufRead + Unpin>(
+    reader: &mut Take<R>,
+) -> Result<String, (u16, String)> {
+    let mut line = String::new();
+    (&mut *reader)
+        .take(MAX_LINE)
+        .read_line(&mut line)
+        .await
+        .map_err(|e| (400, e.to_string()))?;
+    if line.ends_with('\n') {
+        Ok(line)
+    } else if line.len() as u64 == MAX_LINE || reader.limit() == 0 {
+        Err((431, "request header too large".to_string()))
+    } else {
+        Err((400, "incomplete request".to_string()))
+    }
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<HttpRequest, (u16, String)> {
+    let bad_request = |message: &str| (400, message.to_string());
+    let mut reader = BufReader::new(stream).take(MAX_HEAD);
+    let line = read_head_line(&mut reader).await?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().ok_or_else(|| bad_request("missing method"))?;
+    let path = parts.next().ok_or_else(|| bad_request("missing path"))?;
+    let mut request = HttpRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        authorization: None,
+        body: Vec::new(),
+    };
+
+    let mut content_length = 0;
+    for _ in 0..100 {
+        let header = read_head_line(&mut reader).await?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(bad_request("malformed header"));
+        };
+        match name.trim().to_lowercase().as_str() {
+            "content-length" => {
+                content_length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| bad_request("invalid Content-Length"))?
+            }
+            "authorization" => request.authorization = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    if content_length > MAX_BODY {
+        return Err((413, "request body too large".to_string()));
+    }
+    request.body = vec![0; content_length];
+    reader.set_limit(content_length as u64);
+    reader
+        .read_exact(&mut request.body)
+        .await
+        .map_err(|e| bad_request(&e.to_string()))?;
+    Ok(request)
+}
+
+/// Turns an HTTP request into an `ApiRequest`, or the error to answer with.
+fn route(request: &HttpRequest, reply: ApiReply) -> Result<ApiRequest, (u16, String)> {
+    let body: Value = if request.body.is_empty() {
+        json!({})
+    } else {
+        serde_json::from_slice(&request.body).map_err(|e| (400, e.to_string()))?
+    };
+    let field = |name: &str| body.get(name).and_then(Value::as_str).map(str::to_string);
+
+    let path = request.path.trim_end_matches('/');
+    let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["api", "v1", "chats"]) => Ok(ApiRequest::ListChats(reply)),
+        ("POST", ["api", "v1", "chats"]) => Ok(ApiRequest::CreateChat {
+            name: field("name"),
+            model: field("model"),
+            reply,
+        }),
+        ("POST", ["api", "v1", "chats", chat, "messages"]) => Ok(ApiRequest::SendPrompt {
+            chat: percent_decode(chat),
+            prompt: field("prompt")
+                .filter(|prompt| !prompt.trim().is_empty())
+                .ok_or_else(|| (400, "\"prompt\" is required".to_string()))?,
+            reply,
+        }),
+        _ => Err((404, format!("no route for {} {}", request.method, path))),
+    }
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    token: String,
+    mut output: mpsc::Sender<ApiRequest>,
+) {
+    let (reply, mut responses) = mpsc::unbounded();
+    let request = tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream))
+        .await
+        .unwrap_or_else(|_| Err((408, "request timed out".to_string())))
+        .and_then(|request| {
+            if request.authorization.as_deref() != Some(&format!("Bearer {}", token)) {
+                return Err((401, "missing or wrong API token".to_string()));
+            }
+            route(&request, reply)
+        });
+    let request = match request {
+        Ok(request) => request,
+        Err((status, message)) => {
+            let _ = write_json(&mut stream, status, &json!({ "error": message })).await;
+            return;
+        }
+    };
+    if output.send(request).await.is_err() {
+        return;
+    }
+
+    match responses.next().await {
+        Some(ApiResponse::Json(value)) => {
+            let _ = write_json(&mut stream, 200, &value).await;
+        }
+        Some(ApiResponse::Error(status, message)) => {
+            let _ = write_json(&mut stream, status, &json!({ "error": message })).await;
+        }
+        Some(ApiResponse::Line(first)) => {
+            // Streamed replies are newline-delimited JSON; the end of the body is
+            // marked by closing the connection.
+            let head = "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nConnection: close\r\n\r\n";
+            if stream.write_all(head.as_bytes()).await.is_err() {
+                return;
+            }
+            let mut line = Some(first);
+            while let Some(value) = line {
+                if stream
+                    .write_all(format!("{}\n", value).as_bytes())
+                    .await
+                    .is_err()
+                {
+                    return;
+                }
+                line = match responses.next().await {
+                    Some(ApiResponse::Line(value)) => Some(value),
+                    _ => None,
+                };
+            }
+        }
+        Some(ApiResponse::End) | None => {
+            let _ = write_json(&mut stream, 500, &json!({ "error": "no response" })).await;
+        }
+    }
+    let _ = stream.shutdown().await;
+}
+
+async fn write_json(stream: &mut TcpStream, status: u16, value: &Value) -> std::io::Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    };
+    let body = value.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,61 @@
+//! Reads raw requests from a real socket to check the limits on what a client
+//! may send.
+
+use super::*;
+
+/// What `read_request` makes of `raw`, sent by a client that then stops writing.
+fn read(raw: Vec<u8>) -> Result<HttpRequest, (u16, String)> {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let client = tokio::spawn(async move {
+            let mut stream = TcpStream::connect(address).await.unwrap();
+            // The server may answer before reading everything.
+            let _ = stream.write_all(&raw).await;
+            let _ = stream.shutdown().await;
+            stream
+        });
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let request = read_request(&mut stream).await;
+        // Closing first unblocks a client still writing what was not read.
+        drop(stream);
+        drop(client.await);
+        request
+    })
+}
+
+#[test]
+fn reads_headers_and_body() {
+    let request = read(
+        b"POST /api/v1/chats HTTP/1.1\r\nAuthorization: Bearer abc\r\nContent-Length: 4\r\n\r\n{}\r\nextra"
+            .to_vec(),
+    )
+    .unwrap();
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/api/v1/chats");
+    assert_eq!(request.authorization.as_deref(), Some("Bearer abc"));
+    assert_eq!(request.body, b"{}\r\n");
+}
+
+#[test]
+fn rejects_overlong_lines_and_heads() {
+    let mut long_line = b"GET /".to_vec();
+    long_line.extend(std::iter::repeat_n(b'a', MAX_LINE as usize));
+    assert_eq!(read(long_line).err().map(|(status, _)| status), Some(431));
+
+    let mut many_headers = b"GET / HTTP/1.1\r\n".to_vec();
+    for _ in 0..99 {
+        many_headers.extend(format!("X-Padding: {}\r\n", "a".repeat(1000)).as_bytes());
+    }
+    assert_eq!(
+        read(many_headers).err().map(|(status, _)| status),
+        Some(431)
+    );
+}
+
+#[test]
+fn rejects_a_request_cut_short() {
+    let request = read(b"GET / HTTP/1.1\r\nHost: localhost".to_vec());
+    assert_eq!(request.err().map(|(status, _)| status), Some(400));
+}
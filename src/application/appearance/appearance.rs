@@ -0,0 +1,275 @@
+use crate::application::i18n::i18n::{tr, tr_args};
+use iced::theme::{Palette, Theme};
+use iced::{Color, Font};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// User themes, one palette per `.toml` or `.json` file.
+pub const THEMES_DIR: &str = "./themes";
+
+pub const DEFAULT_TEXT_SIZE: f32 = 16.0;
+pub const MIN_TEXT_SIZE: f32 = 8.0;
+pub const MAX_TEXT_SIZE: f32 = 32.0;
+
+pub const MIN_ZOOM: f32 = 0.5;
+pub const MAX_ZOOM: f32 = 2.5;
+pub const ZOOM_STEP: f32 = 0.1;
+
+/// Font family names handed to iced, which only takes `&'static str`. Each
+/// name is leaked once, when it is first applied.
+static FAMILIES: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+/// One of the colors a palette is made of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThemeColor {
+    Background,
+    Text,
+    Primary,
+    Success,
+    Danger,
+}
+
+impl ThemeColor {
+    pub const ALL: [ThemeColor; 5] = [
+        ThemeColor::Background,
+        ThemeColor::Text,
+        ThemeColor::Primary,
+        ThemeColor::Success,
+        ThemeColor::Danger,
+    ];
+
+    /// The message naming this color in the theme editor.
+    pub fn key(&self) -> &'static str {
+        match self {
+            ThemeColor::Background => "theme-background",
+            ThemeColor::Text => "theme-text",
+            ThemeColor::Primary => "theme-primary",
+            ThemeColor::Success => "theme-success",
+            ThemeColor::Danger => "theme-danger",
+        }
+    }
+}
+
+impl std::fmt::Display for ThemeColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", tr(self.key()))
+    }
+}
+
+/// A theme file. Colors are hex strings such as `#1f1f28`.
+///
+/// ```toml
+/// name = "Paper"
+/// background = "#fbf8ef"
+/// text = "#2b2b2b"
+/// primary = "#3b6ea8"
+/// success = "#3f8f4f"
+/// danger = "#b83a3a"
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThemeFile {
+    pub name: String,
+    pub background: String,
+    pub text: String,
+    pub primary: String,
+    pub success: String,
+    pub danger: String,
+}
+
+impl ThemeFile {
+    /// A copy of `theme`'s palette to start editing from.
+    pub fn from_theme(name: String, theme: &Theme) -> Self {
+        let palette = theme.palette();
+        Self {
+            name,
+            background: to_hex(palette.background),
+            text: to_hex(palette.text),
+            primary: to_hex(palette.primary),
+            success: to_hex(palette.success),
+            danger: to_hex(palette.danger),
+        }
+    }
+
+    pub fn color(&self, color: ThemeColor) -> &str {
+        match color {
+            ThemeColor::Background => &self.background,
+            ThemeColor::Text => &self.text,
+            ThemeColor::Primary => &self.primary,
+            ThemeColor::Success => &self.success,
+            ThemeColor::Danger => &self.danger,
+        }
+    }
+
+    pub fn set_color(&mut self, color: ThemeColor, value: String) {
+        match color {
+            ThemeColor::Background => self.background = value,
+            ThemeColor::Text => self.text = value,
+            ThemeColor::Primary => self.primary = value,
+            ThemeColor::Success => self.success = value,
+            ThemeColor::Danger => self.danger = value,
+        }
+    }
+
+    pub fn to_theme(&self) -> Result<Theme, String> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(tr("theme-name-missing"));
+        }
+        if Theme::ALL.iter().any(|theme| theme_key(theme) == name) {
+            return Err(tr_args("theme-name-builtin", &[("name", name.into())]));
+        }
+        let parse = |color: ThemeColor| {
+            let value = self.color(color).trim();
+            Color::parse(value).ok_or_else(|| {
+                tr_args(
+                    "theme-color-invalid",
+                    &[("color", color.to_string().into()), ("value", value.into())],
+                )
+            })
+        };
+        let palette = Palette {
+            background: parse(ThemeColor::Background)?,
+            text: parse(ThemeColor::Text)?,
+            primary: parse(ThemeColor::Primary)?,
+            success: parse(ThemeColor::Success)?,
+            danger: parse(ThemeColor::Danger)?,
+        };
+        Ok(Theme::custom(name.to_string(), palette))
+    }
+
+    /// Writes the theme to `THEMES_DIR` as TOML, replacing a file of the same name.
+    pub fn save(&self) -> Result<PathBuf, String> {
+        self.to_theme()?;
+        let file_name: String = self
+            .name
+            .trim()
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '-'
+                }
+            })
+            .collect();
+        let path = Path::new(THEMES_DIR).join(format!("{}.toml", file_name));
+        let contents = toml::to_string(self).map_err(|e| e.to_string())?;
+        fs::create_dir_all(THEMES_DIR)
+            .and_then(|_| fs::write(&path, contents))
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(path)
+    }
+}
+
+/// Reads every theme in `THEMES_DIR`, sorted by name. Returns a message for
+/// each file that could not be read.
+pub fn load_themes() -> (Vec<Theme>, Vec<String>) {
+    let Ok(entries) = fs::read_dir(THEMES_DIR) else {
+        return (Vec::new(), Vec::new());
+    };
+    let mut themes = Vec::new();
+    let mut errors = Vec::new();
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let parsed = match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|s| toml::from_str::<ThemeFile>(&s).map_err(|e| e.to_string())),
+            Some("json") => fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|s| serde_json::from_str::<ThemeFile>(&s).map_err(|e| e.to_string())),
+            _ => continue,
+        };
+        match parsed.and_then(|file| file.to_theme()) {
+            Ok(theme) if themes.contains(&theme) => {}
+            Ok(theme) => themes.push(theme),
+            Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+    themes.sort_by_key(theme_key);
+    (themes, errors)
+}
+
+/// The name `settings.json` stores for `theme`: the variant name of a built-in
+/// theme, or the name of a custom one.
+pub fn theme_key(theme: &Theme) -> String {
+    match theme {
+        Theme::Custom(custom) => custom.to_string(),
+        builtin => format!("{:?}", builtin),
+    }
+}
+
+/// The built-in or custom theme stored as `key`.
+pub fn find_theme(key: &str, custom: &[Theme]) -> Option<Theme> {
+    Theme::ALL
+        .iter()
+        .chain(custom)
+        .find(|theme| theme_key(theme) == key)
+        .cloned()
+}
+
+/// Whether the operating system is set to dark mode. May block briefly.
+pub fn system_is_dark() -> bool {
+    dark_light::detect() == dark_light::Mode::Dark
+}
+
+/// Fonts chosen in settings. Families must be installed; empty means iced's default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FontSettings {
+    /// Used for the whole interface from the next start.
+    #[serde(default)]
+    pub ui: String,
+    /// Used for code blocks, Modelfiles and logs.
+    #[serde(default)]
+    pub monospace: String,
+    /// Base text size from the next start; zoom applies on top of it.
+    #[serde(default = "default_text_size")]
+    pub size: f32,
+}
+
+impl Default for FontSettings {
+    fn default() -> Self {
+        Self {
+            ui: String::new(),
+            monospace: String::new(),
+            size: DEFAULT_TEXT_SIZE,
+        }
+    }
+}
+
+impl FontSettings {
+    pub fn ui_font(&self) -> Font {
+        font(&self.ui, Font::DEFAULT)
+    }
+
+    pub fn monospace_font(&self) -> Font {
+        font(&self.monospace, Font::MONOSPACE)
+    }
+}
+
+fn default_text_size() -> f32 {
+    DEFAULT_TEXT_SIZE
+}
+
+fn font(family: &str, fallback: Font) -> Font {
+    let family = family.trim();
+    if family.is_empty() {
+        return fallback;
+    }
+    let mut families = FAMILIES.lock().unwrap_or_else(|e| e.into_inner());
+    let name = match families.iter().find(|name| **name == family) {
+        Some(name) => *name,
+        None => {
+            let name: &'static str = Box::leak(family.to_string().into_boxed_str());
+            families.push(name);
+            name
+        }
+    };
+    Font::with_name(name)
+}
+
+fn to_hex(color: Color) -> String {
+    let [r, g, b, _] = color.into_rgba8();
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
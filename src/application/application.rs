@@ -1,38 +1,438 @@
+use crate::application::api::api::{self as automation, ApiReply, ApiRequest, ApiResponse};
+use crate::application::appearance::appearance::{
+    self, find_theme, load_themes, theme_key, FontSettings, ThemeColor, ThemeFile, MAX_TEXT_SIZE,
+    MAX_ZOOM, MIN_TEXT_SIZE, MIN_ZOOM, ZOOM_STEP,
+};
+use crate::application::backend::backend::{transcript, BackendKind, ChatBackend, ChatRequest};
+use crate::application::cli::cli::LaunchOptions;
+use crate::application::diagnostics::diagnostics::{self, http_span, redact, redact_url, traced};
+use crate::application::i18n::i18n::{self, tr, tr_args, Language};
+use crate::application::iced_settings::iced_settings::{WindowGeometry, MIN_SIZE};
+use crate::application::keymap::keymap::{fuzzy_score, Action, Keymap};
+use crate::application::modelfile::modelfile::{format_parameters, parse_parameters, Modelfile};
+use crate::application::prompts::prompts::{PromptLibrary, PromptTemplate};
+use crate::application::servers::servers::{
+    format_headers, load_secrets, normalize_url, parse_headers, save_secrets, AuthKind,
+    ServerHealth, ServerProfile,
+};
+use iced::advanced::widget::{operate, operation, Id as WidgetId, Operation};
 use iced::alignment::{Horizontal, Vertical};
 use iced::border::Radius;
-use iced::futures::{SinkExt, Stream, StreamExt};
+use iced::futures::future::BoxFuture;
+use iced::futures::{FutureExt, SinkExt, Stream, StreamExt};
 use iced::stream::try_channel;
 use iced::theme::Theme as IcedTheme;
-use iced::widget::{button, column, container, progress_bar, row, scrollable, text, text_input};
-use iced::{futures, Alignment, Border, Color, Element, Length, Subscription};
+use iced::widget::{
+    button, checkbox, column, container, mouse_area, progress_bar, row, scrollable, text,
+    text_editor, text_input, vertical_rule,
+};
+use iced::{
+    event, futures, keyboard, mouse, window, Alignment, Border, Color, Element, Length, Point,
+    Rectangle, Size, Subscription, Task,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::HashMap;
 use std::fs;
 use std::hash::Hash;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
 use uuid::Uuid;
 
+/// A model name on a particular server.
+type ModelKey = (Uuid, String);
+
+/// State of the "Test connection" button in the server editor.
+#[derive(Debug, Clone)]
+enum ConnectionTest {
+    Running(Uuid),
+    Succeeded(String),
+    Failed(String),
+}
+
+/// Attempts before a failed pull stops retrying on its own.
+const MAX_DOWNLOAD_ATTEMPTS: u32 = 5;
+
 #[derive(Debug, Serialize, Deserialize)]
 struct LocalModels {
     models: Vec<ModelInfo>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ModelInfo {
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelInfo {
+    pub name: String,
+    pub modified_at: String,
+    pub size: u64,
+    pub digest: String,
+    pub details: ModelDetails,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModelDetails {
+    pub format: String,
+    pub family: String,
+    pub parameter_size: String,
+    pub quantization_level: String,
+}
+
+/// Response of `/api/show`.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ModelShow {
+    #[serde(default)]
+    modelfile: String,
+    #[serde(default)]
+    parameters: String,
+    #[serde(default)]
+    template: String,
+    #[serde(default)]
+    license: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RunningModels {
+    models: Vec<RunningModel>,
+}
+
+/// Entry of `/api/ps`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RunningModel {
     name: String,
-    modified_at: String,
+    #[serde(default)]
     size: u64,
-    digest: String,
-    details: ModelDetails,
+    #[serde(default)]
+    size_vram: u64,
+    #[serde(default)]
+    expires_at: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct ModelDetails {
-    format: String,
-    family: String,
-    parameter_size: String,
-    quantization_level: String,
+/// A request the model manager makes, answered by `Message::ModelCallFinished`.
+#[derive(Debug, Clone)]
+enum ModelCall {
+    List,
+    Show(String),
+    /// Shows the model to open it in the editor.
+    Edit(String),
+    Delete(String),
+    Copy {
+        source: String,
+        destination: String,
+        remove_source: bool,
+    },
+    Running,
+    Unload(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum ModelAnswer {
+    Models(Vec<ModelInfo>),
+    Show(ModelShow),
+    Running(Vec<RunningModel>),
+    Done,
+}
+
+/// A model call waiting for its server.
+#[derive(Debug, Clone)]
+struct PendingModelCall {
+    id: Uuid,
+    server: Uuid,
+    call: ModelCall,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ModelSort {
+    #[default]
+    Name,
+    Size,
+    Modified,
+    Family,
+}
+
+impl ModelSort {
+    const ALL: [ModelSort; 4] = [
+        ModelSort::Name,
+        ModelSort::Size,
+        ModelSort::Modified,
+        ModelSort::Family,
+    ];
+}
+
+impl std::fmt::Display for ModelSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            ModelSort::Name => tr("models-sort-name"),
+            ModelSort::Size => tr("models-sort-size"),
+            ModelSort::Modified => tr("models-sort-modified"),
+            ModelSort::Family => tr("models-sort-family"),
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Offline snapshot of the Ollama library, see `assets/library.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct LibraryCatalog {
+    #[serde(default)]
+    updated: String,
+    models: Vec<LibraryModel>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LibraryModel {
+    name: String,
+    #[serde(default)]
+    description: String,
+    tags: Vec<LibraryTag>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LibraryTag {
+    name: String,
+    size: u64,
+}
+
+#[derive(Debug, Default)]
+struct LibraryBrowser {
+    catalog: LibraryCatalog,
+    search: String,
+    import_path: String,
+    /// Free space in the Ollama models directory, known only for a local server.
+    free_space: Option<u64>,
+    error: Option<String>,
+}
+
+impl LibraryBrowser {
+    fn load() -> Self {
+        let catalog = fs::read_to_string("./settings/library.json")
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_else(|| {
+                serde_json::from_str(include_str!("../../assets/library.json")).unwrap_or_default()
+            });
+        Self {
+            catalog,
+            ..Self::default()
+        }
+    }
+
+    /// Replaces the bundled snapshot with the catalog at `import_path`.
+    fn import(&mut self) {
+        let result = fs::read_to_string(self.import_path.trim())
+            .map_err(|e| e.to_string())
+            .and_then(|contents| {
+                serde_json::from_str::<LibraryCatalog>(&contents)
+                    .map_err(|e| e.to_string())
+                    .map(|catalog| (contents, catalog))
+            });
+        match result {
+            Ok((contents, catalog)) => {
+                let _ = fs::create_dir_all("./settings");
+                if let Err(e) = fs::write("./settings/library.json", contents) {
+                    tracing::error!(error = %e, "could not save the library catalog");
+                }
+                self.catalog = catalog;
+                self.import_path.clear();
+                self.error = None;
+            }
+            Err(e) => {
+                self.error = Some(tr_args("error-message", &[("error", e.to_string().into())]))
+            }
+        }
+    }
+}
+
+/// Where a local Ollama stores its blobs (`OLLAMA_MODELS` or `~/.ollama/models`).
+fn ollama_models_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("OLLAMA_MODELS") {
+        return PathBuf::from(dir);
+    }
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .unwrap_or_default();
+    PathBuf::from(home).join(".ollama").join("models")
+}
+
+fn is_local_url(url: &str) -> bool {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_string))
+        .is_some_and(|host| matches!(host.as_str(), "localhost" | "127.0.0.1" | "[::1]"))
+}
+
+/// Free disk space for the models of the server at `url`, if it runs on this machine.
+fn available_model_space(url: &str) -> Option<u64> {
+    if !is_local_url(url) {
+        return None;
+    }
+    ollama_models_dir()
+        .ancestors()
+        .find(|dir| dir.exists())
+        .and_then(|dir| fs2::available_space(dir).ok())
+}
+
+#[derive(Debug, Default)]
+struct ModelEditor {
+    name: String,
+    from: Option<String>,
+    system: text_editor::Content,
+    template: text_editor::Content,
+    parameters: text_editor::Content,
+    creating: Vec<CreateJob>,
+}
+
+/// A running `/api/create` request and the body it was started with.
+#[derive(Debug)]
+struct CreateJob {
+    progress: DownloadProgress,
+    server: Uuid,
+    body: serde_json::Value,
+}
+
+impl ModelEditor {
+    fn modelfile(&self) -> Modelfile {
+        Modelfile {
+            from: self.from.clone().unwrap_or_default(),
+            system: self.system.text().trim_end().to_string(),
+            template: self.template.text().trim_end().to_string(),
+            parameters: parse_parameters(&self.parameters.text()),
+        }
+    }
+
+    fn create_body(&self) -> serde_json::Value {
+        let modelfile = self.modelfile();
+        let mut body = json!({
+            "model": self.name.trim(),
+            "from": modelfile.from,
+            "parameters": modelfile.parameters_json(),
+            "stream": true,
+        });
+        if !modelfile.system.is_empty() {
+            body["system"] = json!(modelfile.system);
+        }
+        if !modelfile.template.is_empty() {
+            body["template"] = json!(modelfile.template);
+        }
+        body
+    }
+
+    fn errors(&self) -> Vec<String> {
+        let mut errors = self.modelfile().validate();
+        let name = self.name.trim();
+        if name.is_empty() {
+            errors.insert(0, tr("model-editor-name-required"));
+        } else if name.contains(char::is_whitespace) {
+            errors.insert(0, tr("model-editor-name-spaces"));
+        }
+        errors
+    }
+}
+
+#[derive(Debug, Default)]
+struct ModelManager {
+    filter: String,
+    sort: ModelSort,
+    selected: Option<String>,
+    show: Option<ModelShow>,
+    copy_target: String,
+    confirm_delete: Option<String>,
+    running: Vec<RunningModel>,
+    error: Option<String>,
+}
+
+/// Values for a template's `{{variables}}`, asked for before the prompt is used.
+#[derive(Debug)]
+struct PromptForm {
+    chat: Uuid,
+    template: PromptTemplate,
+    values: Vec<(String, String)>,
+}
+
+#[derive(Debug, Default)]
+struct PromptEditor {
+    /// Name of the template being edited, `None` for a new one.
+    original: Option<String>,
+    name: String,
+    command: String,
+    body: text_editor::Content,
+    path: String,
+    status: Option<String>,
+}
+
+/// Most models the arena compares at once.
+const MAX_ARENA_MODELS: usize = 4;
+
+/// One prompt sent to several models at once, each answer streaming into its own column.
+#[derive(Debug, Default)]
+struct Arena {
+    selected: Vec<String>,
+    prompt: String,
+    /// Prompt of the current round, kept after the input is cleared.
+    asked: String,
+    columns: Vec<ArenaColumn>,
+    winner: Option<usize>,
+    /// Server the current round runs on, even if the user switches servers meanwhile.
+    server: Uuid,
+}
+
+#[derive(Debug)]
+struct ArenaColumn {
+    /// Subscription id of this column's stream.
+    id: Uuid,
+    model: String,
+    response: String,
+    state: ChatState,
+    stats: Option<GenerationStats>,
+    error: Option<String>,
+}
+
+impl Arena {
+    fn is_running(&self) -> bool {
+        self.columns
+            .iter()
+            .any(|column| matches!(column.state, ChatState::Streaming))
+    }
+
+    fn can_start(&self) -> bool {
+        (2..=MAX_ARENA_MODELS).contains(&self.selected.len())
+            && !self.prompt.trim().is_empty()
+            && !self.is_running()
+    }
+
+    fn start(&mut self, server: Uuid) {
+        self.server = server;
+        self.asked = self.prompt.trim().to_string();
+        self.prompt.clear();
+        self.winner = None;
+        self.columns = self
+            .selected
+            .iter()
+            .map(|model| ArenaColumn {
+                id: Uuid::new_v4(),
+                model: model.clone(),
+                response: String::new(),
+                state: ChatState::Streaming,
+                stats: None,
+                error: None,
+            })
+            .collect();
+    }
+
+    fn progress(&mut self, id: Uuid, progress: Result<OllamaStreamProgress, Error>) {
+        let Some(column) = self.columns.iter_mut().find(|c| c.id == id) else {
+            return;
+        };
+        match progress {
+            Ok(OllamaStreamProgress::Streaming { token }) => column.response.push_str(&token),
+            Ok(OllamaStreamProgress::Finished { stats, .. }) => {
+                column.stats = Some(stats);
+                column.state = ChatState::Finished;
+            }
+            Err(e) => {
+                column.error = Some(tr_args("error-message", &[("error", e.to_string().into())]));
+                column.state = ChatState::Errored;
+            }
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -40,14 +440,263 @@ pub struct AppSettings {
     theme: String,
     default_url: String,
     selected_model: String,
+    #[serde(default = "default_max_downloads")]
+    max_concurrent_downloads: usize,
+    #[serde(default)]
+    servers: Vec<ServerProfile>,
+    #[serde(default)]
+    active_server: Option<Uuid>,
+    #[serde(default = "default_auto_titles")]
+    auto_titles: bool,
+    /// Model used for titles; `None` uses the chat's own model.
+    #[serde(default)]
+    title_model: Option<String>,
+    #[serde(default)]
+    send_key: SendKey,
+    /// Serve the automation API on localhost.
+    #[serde(default)]
+    api_enabled: bool,
+    #[serde(default = "default_api_port")]
+    api_port: u16,
+    #[serde(default)]
+    window: WindowGeometry,
+    #[serde(default = "default_sidebar_width")]
+    sidebar_width: f32,
+    #[serde(default)]
+    sidebar_collapsed: bool,
+    /// Use `light_theme` or `dark_theme` to match the operating system.
+    #[serde(default)]
+    follow_system_theme: bool,
+    #[serde(default = "default_light_theme")]
+    light_theme: String,
+    #[serde(default = "default_dark_theme")]
+    dark_theme: String,
+    #[serde(default)]
+    fonts: FontSettings,
+    #[serde(default = "default_zoom")]
+    zoom: f32,
+    #[serde(default = "Language::from_system")]
+    language: Language,
+}
+
+impl AppSettings {
+    /// Fills in the server profiles and their secrets and returns the active one's id.
+    /// Settings written before server profiles existed only have `default_url`.
+    pub(crate) fn prepare_servers(&mut self) -> Uuid {
+        if self.servers.is_empty() {
+            self.servers
+                .push(ServerProfile::new(&tr("server-local"), &self.default_url));
+        }
+        load_secrets(&mut self.servers);
+        self.active_server
+            .filter(|id| self.servers.iter().any(|s| s.id == *id))
+            .unwrap_or(self.servers[0].id)
+    }
+
+    pub(crate) fn servers(&self) -> &[ServerProfile] {
+        &self.servers
+    }
+
+    pub(crate) fn selected_model(&self) -> &str {
+        &self.selected_model
+    }
+
+    pub(crate) fn window(&self) -> &WindowGeometry {
+        &self.window
+    }
+
+    pub(crate) fn fonts(&self) -> &FontSettings {
+        &self.fonts
+    }
+}
+
+/// Settings the command line or environment replaced for this run, as
+/// `(override, saved)` pairs. `save_settings` keeps writing the saved value
+/// until the setting is changed in the window.
+#[derive(Debug, Default)]
+struct SessionOverrides {
+    theme: Option<(String, String)>,
+    model: Option<(String, String)>,
+    server: Option<(Uuid, Uuid)>,
+    window_size: Option<(Size, Size)>,
+    /// Profile made for a `--url` no saved profile has. It is saved only once
+    /// the user saves it in the server editor.
+    temporary_server: Option<Uuid>,
+}
+
+impl SessionOverrides {
+    /// The value to save for a setting that is currently `current`.
+    fn saved<T: PartialEq + Clone>(pair: &Option<(T, T)>, current: T) -> T {
+        match pair {
+            Some((overridden, saved)) if *overridden == current => saved.clone(),
+            _ => current,
+        }
+    }
+}
+
+/// Which Enter combination sends the prompt; the other one inserts a newline.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SendKey {
+    #[default]
+    Enter,
+    ShiftEnter,
+}
+
+impl SendKey {
+    pub const ALL: [SendKey; 2] = [SendKey::Enter, SendKey::ShiftEnter];
+}
+
+impl std::fmt::Display for SendKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendKey::Enter => write!(f, "{}", tr("settings-send-enter")),
+            SendKey::ShiftEnter => write!(f, "{}", tr("settings-send-shift-enter")),
+        }
+    }
+}
+
+/// The prompt editor grows with its content up to this many lines, then scrolls.
+const MAX_PROMPT_LINES: usize = 10;
+
+const DEFAULT_CHAT_NAME: &str = "New Unnamed Chat";
+
+fn default_max_downloads() -> usize {
+    2
+}
+
+fn default_auto_titles() -> bool {
+    true
+}
+
+/// Used when the saved theme no longer exists.
+fn default_theme() -> IcedTheme {
+    IcedTheme::KanagawaDragon
+}
+
+fn default_light_theme() -> String {
+    format!("{:?}", IcedTheme::Light)
+}
+
+fn default_dark_theme() -> String {
+    format!("{:?}", default_theme())
+}
+
+fn default_zoom() -> f32 {
+    1.0
+}
+
+fn default_sidebar_width() -> f32 {
+    240.0
+}
+
+fn default_api_port() -> u16 {
+    automation::DEFAULT_PORT
+}
+
+/// Container around the chat's prompt editor, which `FocusWithin` targets.
+const PROMPT_INPUT: &str = "prompt-input";
+const CHAT_SEARCH: &str = "chat-search";
+const PALETTE_INPUT: &str = "command-palette";
+
+/// Most entries the command palette lists at once.
+const MAX_PALETTE_ENTRIES: usize = 10;
+
+/// Events listed in the Diagnostics view; the bundle has all of them.
+const MAX_DIAGNOSTICS_EVENTS: usize = 200;
+
+/// Narrowest the sidebar can be dragged; it can take up to half the window.
+const MIN_SIDEBAR_WIDTH: f32 = 160.0;
+
+/// Below this the layout tightens and the sidebar is capped at `COMPACT_SIDEBAR_WIDTH`.
+const COMPACT_SIZE: Size = Size::new(MIN_SIZE.width + 140.0, MIN_SIZE.height + 80.0);
+const COMPACT_SIDEBAR_WIDTH: f32 = 200.0;
+
+/// The Ctrl+K palette: a query and the highlighted entry.
+#[derive(Debug, Default)]
+struct CommandPalette {
+    query: String,
+    selected: usize,
+}
+
+#[derive(Debug, Clone)]
+enum PaletteEntry {
+    Action(Action),
+    Chat(Uuid, String),
+    Model(String),
+}
+
+impl std::fmt::Display for PaletteEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PaletteEntry::Action(action) => write!(f, "{}", action),
+            PaletteEntry::Chat(_, name) => write!(
+                f,
+                "{}",
+                tr_args("palette-chat", &[("name", name.as_str().into())])
+            ),
+            PaletteEntry::Model(name) => write!(
+                f,
+                "{}",
+                tr_args("palette-model", &[("name", name.as_str().into())])
+            ),
+        }
+    }
+}
+
+/// Focuses the widgets inside the container with `target` and unfocuses every
+/// other one. `text_editor` has no id of its own, so it is found by its container.
+struct FocusWithin {
+    target: WidgetId,
+    inside: bool,
+}
+
+impl<T> Operation<T> for FocusWithin {
+    fn container(
+        &mut self,
+        id: Option<&WidgetId>,
+        _bounds: Rectangle,
+        operate_on_children: &mut dyn FnMut(&mut dyn Operation<T>),
+    ) {
+        let entering = !self.inside && id == Some(&self.target);
+        self.inside |= entering;
+        operate_on_children(self);
+        if entering {
+            self.inside = false;
+        }
+    }
+
+    fn focusable(&mut self, state: &mut dyn operation::Focusable, _id: Option<&WidgetId>) {
+        if self.inside {
+            state.focus();
+        } else {
+            state.unfocus();
+        }
+    }
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
-            theme: format!("{:?}", IcedTheme::KanagawaDragon),
+            theme: format!("{:?}", default_theme()),
             default_url: "http://localhost:11434".to_string(),
             selected_model: "llama3.2".to_string(),
+            max_concurrent_downloads: default_max_downloads(),
+            servers: Vec::new(),
+            active_server: None,
+            auto_titles: default_auto_titles(),
+            title_model: None,
+            send_key: SendKey::default(),
+            api_enabled: false,
+            api_port: default_api_port(),
+            window: WindowGeometry::default(),
+            sidebar_width: default_sidebar_width(),
+            sidebar_collapsed: false,
+            follow_system_theme: false,
+            light_theme: default_light_theme(),
+            dark_theme: default_dark_theme(),
+            fonts: FontSettings::default(),
+            zoom: default_zoom(),
+            language: Language::from_system(),
         }
     }
 }
@@ -56,6 +705,23 @@ impl Default for AppSettings {
 pub enum AppState {
     Chat,
     Settings,
+    Statistics,
+    Models,
+    ModelEditor,
+    Library,
+    Arena,
+    Prompts,
+    Diagnostics,
+}
+
+impl AppState {
+    /// Views that only work against a server that manages its own models.
+    fn needs_model_management(&self) -> bool {
+        matches!(
+            self,
+            AppState::Models | AppState::ModelEditor | AppState::Library
+        )
+    }
 }
 
 #[derive(Debug)]
@@ -64,12 +730,93 @@ pub struct OllamaGUI {
     current_chat: Uuid,
     editing_chat: Option<Uuid>,
     state: AppState,
-    default_url: String,
+    servers: Vec<ServerProfile>,
+    active_server: Uuid,
+    /// One client per server so connections are reused across requests.
+    server_clients: HashMap<Uuid, reqwest::Client>,
+    /// Profile being edited in the settings view.
+    server_draft: ServerProfile,
+    /// Extra headers of the draft, one `Name: value` per line.
+    server_headers: text_editor::Content,
+    connection_test: Option<ConnectionTest>,
+    /// Last `/api/version` result per server.
+    health: HashMap<Uuid, ServerHealth>,
     theme: iced::Theme,
     download_model_input: String,
-    download_progress: Vec<DownloadProgress>,
-    local_models: Vec<String>,
+    downloads: Vec<DownloadJob>,
+    max_concurrent_downloads: usize,
+    notice: Option<String>,
+    /// Models installed on each server.
+    local_models: HashMap<Uuid, Vec<ModelInfo>>,
+    model_manager: ModelManager,
+    model_calls: Vec<PendingModelCall>,
+    model_editor: ModelEditor,
+    library: LibraryBrowser,
+    arena: Arena,
+    auto_titles: bool,
+    title_model: Option<String>,
+    send_key: SendKey,
+    prompts: PromptLibrary,
+    prompt_form: Option<PromptForm>,
+    prompt_editor: PromptEditor,
+    keymap: Keymap,
+    palette: Option<CommandPalette>,
+    api_enabled: bool,
+    api_port: u16,
+    /// The port field as typed; `api_port` changes once it is submitted.
+    api_port_input: String,
+    /// Token API clients authenticate with; `None` if it could not be written.
+    api_token: Option<String>,
+    /// Why the API is not listening, if it failed to start, or why the typed
+    /// port was not taken.
+    api_error: Option<String>,
+    /// API clients waiting for the reply of each chat.
+    api_listeners: HashMap<Uuid, Vec<ApiReply>>,
+    /// Filter for the chat list in the sidebar.
+    chat_search: String,
     selected_model: String,
+    context_lengths: HashMap<ModelKey, Option<u64>>,
+    /// Failed context length lookups per model, so the next one backs off.
+    context_length_attempts: HashMap<ModelKey, u32>,
+    overrides: SessionOverrides,
+    /// Geometry saved when the window last closed.
+    window: WindowGeometry,
+    window_size: Size,
+    window_position: Option<Point>,
+    sidebar_width: f32,
+    sidebar_collapsed: bool,
+    /// Whether the splitter next to the sidebar is being dragged.
+    dragging_sidebar: bool,
+    /// Themes from `THEMES_DIR`.
+    custom_themes: Vec<IcedTheme>,
+    follow_system_theme: bool,
+    light_theme: IcedTheme,
+    dark_theme: IcedTheme,
+    system_dark: bool,
+    /// Theme being edited; the window previews it while it is valid.
+    theme_editor: Option<ThemeFile>,
+    fonts: FontSettings,
+    font_form: FontForm,
+    zoom: f32,
+    language: Language,
+}
+
+/// The font settings as typed, applied together.
+#[derive(Debug, Clone, Default)]
+struct FontForm {
+    ui: String,
+    monospace: String,
+    size: String,
+}
+
+impl FontForm {
+    fn new(fonts: &FontSettings) -> Self {
+        Self {
+            ui: fonts.ui.clone(),
+            monospace: fonts.monospace.clone(),
+            size: fonts.size.to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -81,13 +828,218 @@ pub struct DownloadProgress {
     pub completed: u64,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+enum DownloadState {
+    Queued,
+    Active,
+    /// Gave up after `MAX_DOWNLOAD_ATTEMPTS`; waits for a manual retry.
+    Failed,
+}
+
+/// A model pull in the download queue.
+#[derive(Debug)]
+struct DownloadJob {
+    progress: DownloadProgress,
+    server: Uuid,
+    url: String,
+    state: DownloadState,
+    /// Failed attempts so far; also part of the subscription id so each retry
+    /// starts a fresh request.
+    attempts: u32,
+    /// Per blob `(digest, total, completed)`, in the order Ollama reported them.
+    layers: Vec<(String, u64, u64)>,
+    /// Smoothed transfer speed in bytes per second.
+    speed: f64,
+    last_sample: Option<(Instant, u64)>,
+}
+
+impl DownloadJob {
+    fn new(id: Uuid, model: String, server: Uuid, url: String) -> Self {
+        Self {
+            progress: DownloadProgress {
+                id,
+                model,
+                status: tr("download-queued"),
+                total: 0,
+                completed: 0,
+            },
+            server,
+            url,
+            state: DownloadState::Queued,
+            attempts: 0,
+            layers: Vec::new(),
+            speed: 0.0,
+            last_sample: None,
+        }
+    }
+
+    fn update(&mut self, status: String, digest: String, total: u64, completed: u64) {
+        if !digest.is_empty() && total > 0 {
+            match self.layers.iter_mut().find(|(d, _, _)| *d == digest) {
+                Some(layer) => *layer = (digest, total, completed),
+                None => self.layers.push((digest, total, completed)),
+            }
+        }
+        self.progress.status = status;
+        self.progress.total = self.layers.iter().map(|(_, t, _)| t).sum();
+        self.progress.completed = self.layers.iter().map(|(_, _, c)| c).sum();
+
+        let now = Instant::now();
+        match self.last_sample {
+            Some((at, bytes)) if now.duration_since(at) >= Duration::from_secs(1) => {
+                let elapsed = now.duration_since(at).as_secs_f64();
+                let current = self.progress.completed.saturating_sub(bytes) as f64 / elapsed;
+                self.speed = if self.speed == 0.0 {
+                    current
+                } else {
+                    self.speed * 0.7 + current * 0.3
+                };
+                self.last_sample = Some((now, self.progress.completed));
+            }
+            Some(_) => {}
+            None => self.last_sample = Some((now, self.progress.completed)),
+        }
+    }
+
+    fn eta(&self) -> Option<Duration> {
+        let remaining = self.progress.total.saturating_sub(self.progress.completed);
+        (self.speed > 0.0 && remaining > 0)
+            .then(|| Duration::from_secs_f64(remaining as f64 / self.speed))
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let id = self.progress.id;
+        let mut details = vec![];
+        if self.state == DownloadState::Active && self.speed > 0.0 {
+            let speed = format_size(self.speed as u64);
+            let label = match self.eta() {
+                Some(eta) => tr_args(
+                    "download-speed-eta",
+                    &[
+                        ("speed", speed.into()),
+                        ("eta", format_duration(eta.as_nanos() as u64).into()),
+                    ],
+                ),
+                None => tr_args("download-speed", &[("speed", speed.into())]),
+            };
+            details.push(text(label).size(12));
+        }
+        for (digest, total, completed) in &self.layers {
+            let digest = digest.trim_start_matches("sha256:");
+            details.push(
+                text(format!(
+                    "{}: {} / {}",
+                    &digest[..digest.len().min(12)],
+                    format_size(*completed),
+                    format_size(*total)
+                ))
+                .size(12),
+            );
+        }
+
+        column![
+            row![progress_row(
+                &self.progress,
+                &tr("download-downloading"),
+                Message::CancelDownload(id)
+            )]
+            .push_maybe(
+                (self.state == DownloadState::Failed).then(
+                    || button(text(tr("download-retry"))).on_press(Message::RetryDownload(id))
+                )
+            )
+            .spacing(10)
+            .align_y(Alignment::Center),
+            column(details.into_iter().map(Element::from)).spacing(2),
+        ]
+        .spacing(5)
+        .into()
+    }
+}
+
+/// What is written to `downloads.json` so pulls survive a restart.
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedDownload {
+    id: Uuid,
+    model: String,
+    url: String,
+    #[serde(default)]
+    server: Option<Uuid>,
+}
+
+fn retry_delay(attempt: u32) -> Duration {
+    Duration::from_secs((1u64 << attempt.min(6)).min(60))
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     NewChat,
     StartChat(Uuid),
     ChatProgress((Uuid, Result<OllamaStreamProgress, Error>)),
+    CancelQueuedPrompt(Uuid),
+    ChangeChatModel(Uuid, String),
+    RegenerateTitle(Uuid),
+    InsertPrompt(Uuid, PromptTemplate),
+    PromptFormChanged(usize, String),
+    SubmitPromptForm(bool),
+    CancelPromptForm,
+    EditPromptTemplate(PromptTemplate),
+    NewPromptTemplate,
+    PromptTemplateNameChanged(String),
+    PromptTemplateCommandChanged(String),
+    PromptTemplateBodyAction(text_editor::Action),
+    SavePromptTemplate,
+    DeletePromptTemplate(String),
+    PromptLibraryPathChanged(String),
+    ImportPrompts,
+    ExportPrompts,
+    TitleGenerated(Uuid, Result<String, Error>),
+    ToggleAutoTitles(bool),
+    ChangeTitleModel(String),
+    HealthChecked(Uuid, Result<String, Error>),
     SelectChat(Uuid),
-    PromptChanged(Uuid, String),
+    StopChat(Uuid),
+    RegenerateResponse(Uuid),
+    ChatSearchChanged(String),
+    /// A key press, and whether a widget already handled it.
+    KeyPressed(keyboard::Key, keyboard::Modifiers, bool),
+    PaletteQueryChanged(String),
+    Api(ApiRequest),
+    ToggleApi(bool),
+    ChangeApiPort(String),
+    ApplyApiPort,
+    CopyDiagnostics,
+    WindowResized(Size),
+    WindowMoved(Point),
+    WindowCloseRequested(window::Id),
+    /// Saves the geometry and closes; the flag is whether the window is maximized.
+    CloseWindow(window::Id, bool),
+    ToggleSidebar,
+    ChangeLightTheme(iced::Theme),
+    ChangeDarkTheme(iced::Theme),
+    FollowSystemTheme(bool),
+    CheckSystemTheme,
+    SystemThemeChecked(bool),
+    EditTheme,
+    ThemeEditorName(String),
+    ThemeEditorColor(ThemeColor, String),
+    SaveTheme,
+    CancelThemeEdit,
+    ReloadThemes,
+    ChangeUiFont(String),
+    ChangeMonospaceFont(String),
+    ChangeTextSize(String),
+    ApplyFonts,
+    Zoom(f32),
+    ChangeLanguage(Language),
+    SidebarDragStarted,
+    SidebarDragged(f32),
+    SidebarDragEnded,
+    PaletteSelect(usize),
+    ClosePalette,
+    PromptAction(Uuid, text_editor::Action),
+    SaveDrafts,
+    ChangeSendKey(SendKey),
     StartRenameChat(Uuid),
     FinishRenameChat(Uuid),
     CancelRenameChat(Uuid),
@@ -95,19 +1047,79 @@ pub enum Message {
     DeleteChat(Uuid),
     ChangeAppState(AppState),
     ChangeTheme(iced::Theme),
+    SelectServer(ServerProfile),
+    EditServer(ServerProfile),
+    NewServer,
+    ServerNameChanged(String),
     ChangeDefaultUrl(String),
+    ServerBackendChanged(BackendKind),
+    ServerAuthKindChanged(AuthKind),
+    ServerUsernameChanged(String),
+    ServerPasswordChanged(String),
+    ServerTokenChanged(String),
+    ServerHeadersAction(text_editor::Action),
+    ServerCaCertChanged(String),
+    ServerIdentityChanged(String),
+    ServerInsecureToggled(bool),
+    ServerTimeoutChanged(String),
+    TestConnection,
+    ConnectionTested(Uuid, Result<String, Error>),
+    SaveServer,
+    DeleteServer(Uuid),
     DownloadModelInputChanged(String),
     StartDownloadModel,
     DownloadProgress(Uuid, Result<DownloadProgressUpdate, Error>),
     CancelDownload(Uuid),
+    RetryDownload(Uuid),
+    ChangeMaxDownloads(usize),
+    DismissNotice,
     ChangeSelectedModel(String),
     RefreshModels,
+    ContextLengthLoaded(ModelKey, Result<Option<u64>, Error>),
+    ChangeNumCtx(Uuid, String),
+    SubmitNumCtx(Uuid),
+    DropOldestTurn(Uuid),
+    SummarizeHistory(Uuid),
+    ContinueInNewChat(Uuid),
+    SummaryFinished(Uuid, Result<String, Error>),
+    ModelFilterChanged(String),
+    ModelSortChanged(ModelSort),
+    SelectManagedModel(String),
+    ModelCallFinished(Uuid, Result<ModelAnswer, Error>),
+    CopyTargetChanged(String),
+    CopyModel,
+    RenameModel,
+    DeleteModel(String),
+    ConfirmDeleteModel(String),
+    RefreshRunningModels,
+    UnloadModel(String),
+    NewCustomModel,
+    EditModel(String),
+    ModelEditorNameChanged(String),
+    ModelEditorFromChanged(String),
+    ModelEditorSystemAction(text_editor::Action),
+    ModelEditorTemplateAction(text_editor::Action),
+    ModelEditorParametersAction(text_editor::Action),
+    CreateModel,
+    CreateProgress(Uuid, Result<DownloadProgressUpdate, Error>),
+    DismissCreate(Uuid),
+    LibrarySearchChanged(String),
+    LibraryImportPathChanged(String),
+    ImportLibrary,
+    PullLibraryModel(String),
+    ArenaToggleModel(String, bool),
+    ArenaPromptChanged(String),
+    StartArena,
+    ArenaProgress((Uuid, Result<OllamaStreamProgress, Error>)),
+    ArenaVote(usize),
+    ContinueArenaWinner,
 }
 
 #[derive(Debug, Clone)]
 pub enum DownloadProgressUpdate {
     Progress {
         status: String,
+        digest: String,
         total: u64,
         completed: u64, //will probably use bool in the future
     },
@@ -116,36 +1128,213 @@ pub enum DownloadProgressUpdate {
 
 #[derive(Debug, Clone)]
 pub enum OllamaStreamProgress {
-    Streaming { token: String },
-    Finished { context: Vec<u64> },
+    Streaming {
+        token: String,
+    },
+    Finished {
+        context: Vec<u64>,
+        stats: GenerationStats,
+    },
 }
 
-#[derive(Debug, Clone)]
-pub enum Error {
-    RequestFailed(Arc<reqwest::Error>), // keep for future debugging
-    ParseError(Arc<serde_json::Error>), // keep for future debugging
-    ChannelError(Arc<futures::channel::mpsc::SendError>), // keep for future debugging
+/// Timings and token counts reported by Ollama in the final `done` chunk.
+/// All durations are in nanoseconds.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GenerationStats {
+    #[serde(default)]
+    pub total_duration: u64,
+    #[serde(default)]
+    pub load_duration: u64,
+    #[serde(default)]
+    pub prompt_eval_count: u64,
+    #[serde(default)]
+    pub prompt_eval_duration: u64,
+    #[serde(default)]
+    pub eval_count: u64,
+    #[serde(default)]
+    pub eval_duration: u64,
 }
 
-impl From<reqwest::Error> for Error {
-    fn from(error: reqwest::Error) -> Self {
-        Error::RequestFailed(Arc::new(error))
+impl GenerationStats {
+    pub fn tokens_per_second(&self) -> f64 {
+        if self.eval_duration == 0 {
+            0.0
+        } else {
+            self.eval_count as f64 / (self.eval_duration as f64 / 1e9)
+        }
     }
-}
 
-impl From<futures::channel::mpsc::SendError> for Error {
-    fn from(e: futures::channel::mpsc::SendError) -> Self {
-        Error::ChannelError(Arc::new(e))
+    /// Time until the first generated token, i.e. model load plus prompt evaluation.
+    pub fn time_to_first_token(&self) -> u64 {
+        self.load_duration + self.prompt_eval_duration
+    }
+
+    fn footer(&self) -> String {
+        tr_args(
+            "chat-stats",
+            &[
+                ("tokens", self.eval_count.into()),
+                ("speed", format!("{:.1}", self.tokens_per_second()).into()),
+                ("first", format_duration(self.time_to_first_token()).into()),
+                ("load", format_duration(self.load_duration).into()),
+            ],
+        )
     }
 }
 
-impl OllamaGUI {
+/// Running totals over many responses, used by the statistics view.
+#[derive(Debug, Clone, Default)]
+struct StatsSummary {
+    responses: u64,
+    eval_count: u64,
+    eval_duration: u64,
+    time_to_first_token: u64,
+    load_duration: u64,
+}
+
+impl StatsSummary {
+    fn add(&mut self, stats: &GenerationStats) {
+        self.responses += 1;
+        self.eval_count += stats.eval_count;
+        self.eval_duration += stats.eval_duration;
+        self.time_to_first_token += stats.time_to_first_token();
+        self.load_duration += stats.load_duration;
+    }
+
+    fn tokens_per_second(&self) -> f64 {
+        if self.eval_duration == 0 {
+            0.0
+        } else {
+            self.eval_count as f64 / (self.eval_duration as f64 / 1e9)
+        }
+    }
+
+    fn view<'a>(&self, label: &str) -> Element<'a, Message> {
+        let responses = self.responses.max(1);
+        row![
+            text(label.to_string()).width(Length::FillPortion(3)),
+            text(self.responses).width(Length::FillPortion(1)),
+            text(self.eval_count).width(Length::FillPortion(1)),
+            text(format!("{:.1}", self.tokens_per_second())).width(Length::FillPortion(1)),
+            text(format_duration(self.time_to_first_token / responses))
+                .width(Length::FillPortion(1)),
+            text(format_duration(self.load_duration / responses)).width(Length::FillPortion(1)),
+        ]
+        .spacing(10)
+        .into()
+    }
+}
+
+fn progress_row<'a>(
+    progress: &'a DownloadProgress,
+    verb: &str,
+    on_cancel: Message,
+) -> Element<'a, Message> {
+    let fraction = if progress.total > 0 {
+        progress.completed as f32 / progress.total as f32
+    } else {
+        0.0
+    };
+    row![
+        column![
+            text(format!(
+                "{} {}: {}%",
+                verb,
+                progress.model,
+                (fraction * 100.0) as u32
+            ))
+            .size(16),
+            progress_bar::<iced::Theme>(0.0..=1.0, fraction).width(Length::Fixed(300.0)),
+            text(&progress.status)
+        ]
+        .padding([5, 0])
+        .spacing(5),
+        button(text(tr("progress-cancel")))
+            .on_press(on_cancel)
+            .padding([5, 10])
+    ]
+    .align_y(Vertical::Center)
+    .spacing(10)
+    .into()
+}
+
+pub(crate) fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+fn format_duration(nanos: u64) -> String {
+    let millis = nanos / 1_000_000;
+    if millis >= 1000 {
+        format!("{:.2}s", millis as f64 / 1000.0)
+    } else {
+        format!("{}ms", millis)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Error {
+    RequestFailed(Arc<reqwest::Error>), // keep for future debugging
+    ParseError(Arc<serde_json::Error>), // keep for future debugging
+    ChannelError(Arc<futures::channel::mpsc::SendError>), // keep for future debugging
+    Ollama(String),
+}
+
+impl Error {
+    /// True when the server could not be reached at all, as opposed to rejecting the request.
+    fn is_connection_error(&self) -> bool {
+        match self {
+            // A timeout may just be a slow model load, so it is not retried.
+            Error::RequestFailed(e) => e.is_connect(),
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::RequestFailed(e) => write!(f, "{}", e),
+            Error::ParseError(e) => write!(f, "{}", e),
+            Error::ChannelError(e) => write!(f, "{}", e),
+            // The message the server sent back, e.g. a missing model.
+            Error::Ollama(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(error: reqwest::Error) -> Self {
+        Error::RequestFailed(Arc::new(error))
+    }
+}
+
+impl From<futures::channel::mpsc::SendError> for Error {
+    fn from(e: futures::channel::mpsc::SendError) -> Self {
+        Error::ChannelError(Arc::new(e))
+    }
+}
+
+impl OllamaGUI {
+    #[tracing::instrument(level = "debug")]
     pub fn load_settings() -> AppSettings {
         let path = PathBuf::from("./settings/settings.json");
         if path.exists() {
             match fs::read_to_string(&path) {
-                Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
-                Err(_) => AppSettings::default(),
+                Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                    tracing::error!(error = %e, "settings.json is invalid, using defaults");
+                    AppSettings::default()
+                }),
+                Err(e) => {
+                    tracing::error!(error = %e, "could not read settings.json");
+                    AppSettings::default()
+                }
             }
         } else {
             let _ = fs::create_dir_all("./settings");
@@ -153,389 +1342,3371 @@ impl OllamaGUI {
         }
     }
 
+    /// The active server profile.
+    fn server(&self) -> &ServerProfile {
+        self.server_by_id(self.active_server)
+    }
+
+    /// The profile with `id`, or the active one if it no longer exists.
+    fn server_by_id(&self, id: Uuid) -> &ServerProfile {
+        self.servers
+            .iter()
+            .find(|s| s.id == id)
+            .or_else(|| self.servers.iter().find(|s| s.id == self.active_server))
+            .unwrap_or(&self.servers[0])
+    }
+
+    /// The server's client, or why its auth or TLS settings cannot make one.
+    fn client(&self, server: Uuid) -> Result<reqwest::Client, String> {
+        match self.server_clients.get(&server) {
+            Some(client) => Ok(client.clone()),
+            None => self.server_by_id(server).client(),
+        }
+    }
+
+    fn manages_models(&self) -> bool {
+        self.server().backend.manages_models()
+    }
+
+    /// Goes back to the chat when the active server cannot back the open view.
+    fn leave_unsupported_view(&mut self) {
+        if self.state.needs_model_management() && !self.manages_models() {
+            self.state = AppState::Chat;
+        }
+    }
+
+    fn edit_server(&mut self, server: ServerProfile) {
+        self.connection_test = None;
+        self.server_headers =
+            text_editor::Content::with_text(&format_headers(&server.secrets.headers));
+        self.server_draft = server;
+    }
+
+    fn health(&self, server: Uuid) -> ServerHealth {
+        self.health.get(&server).cloned().unwrap_or_default()
+    }
+
+    fn model_infos(&self) -> &[ModelInfo] {
+        self.local_models
+            .get(&self.active_server)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// The title model if the chat's server has it, the chat's own model otherwise.
+    fn title_model_for(&self, chat: &OllamaChat) -> String {
+        let server = self.server_by_id(chat.server).id;
+        self.title_model
+            .clone()
+            .filter(|title_model| {
+                self.local_models
+                    .get(&server)
+                    .is_some_and(|models| models.iter().any(|m| m.name == *title_model))
+            })
+            .unwrap_or_else(|| chat.model.clone())
+    }
+
+    fn model_names(&self) -> Vec<String> {
+        self.model_infos().iter().map(|m| m.name.clone()).collect()
+    }
+
+    /// Sends `call` to the active server.
+    fn call_models(&mut self, call: ModelCall) {
+        self.model_calls.push(PendingModelCall {
+            id: Uuid::new_v4(),
+            server: self.active_server,
+            call,
+        });
+    }
+
     fn load_local_models(&mut self) {
-        let client = reqwest::blocking::Client::new();
-        let url = format!("{}/api/tags", self.default_url);
+        self.call_models(ModelCall::List);
+    }
+
+    fn load_running_models(&mut self) {
+        self.call_models(ModelCall::Running);
+    }
 
-        match client.get(&url).send() {
-            Ok(response) => {
-                if let Ok(models) = response.json::<LocalModels>() {
-                    self.local_models = models.models.iter().map(|m| m.name.clone()).collect();
+    fn model_call_finished(&mut self, id: Uuid, result: Result<ModelAnswer, Error>) {
+        let Some(index) = self.model_calls.iter().position(|c| c.id == id) else {
+            return;
+        };
+        let PendingModelCall { server, call, .. } = self.model_calls.remove(index);
+        if let ModelCall::List = call {
+            match result {
+                Ok(ModelAnswer::Models(models)) => {
+                    let models = LocalModels { models };
+                    self.local_models.insert(server, models.models.clone());
 
                     // Save to models.json
                     let path = PathBuf::from("./settings/models.json");
                     let _ = fs::create_dir_all("./settings");
-                    let _ = fs::write(path, serde_json::to_string_pretty(&models).unwrap());
+                    if let Err(e) = fs::write(path, serde_json::to_string_pretty(&models).unwrap())
+                    {
+                        tracing::error!(error = %e, "could not save models.json");
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!(error = ?e, "could not load models"),
+            }
+            return;
+        }
+        // The model manager only shows the active server.
+        if server != self.active_server {
+            return;
+        }
+        let answer = match result {
+            Ok(answer) => answer,
+            Err(e) => {
+                self.model_manager.error =
+                    Some(tr_args("error-message", &[("error", e.to_string().into())]));
+                return;
+            }
+        };
+        match (call, answer) {
+            (ModelCall::Show(name), ModelAnswer::Show(show)) => {
+                self.model_manager.show = Some(show);
+                self.model_manager.selected = Some(name);
+                self.model_manager.confirm_delete = None;
+                self.model_manager.error = None;
+            }
+            (ModelCall::Edit(name), ModelAnswer::Show(show)) => {
+                let system = Modelfile::parse(&show.modelfile)
+                    .map(|m| m.system)
+                    .unwrap_or_default();
+                self.model_editor = ModelEditor {
+                    creating: std::mem::take(&mut self.model_editor.creating),
+                    name: name.clone(),
+                    from: Some(name),
+                    system: text_editor::Content::with_text(&system),
+                    template: text_editor::Content::with_text(&show.template),
+                    parameters: text_editor::Content::with_text(&format_parameters(
+                        &parse_parameters(&show.parameters),
+                    )),
+                };
+                self.state = AppState::ModelEditor;
+            }
+            (ModelCall::Delete(name), _) => {
+                self.model_manager.error = None;
+                if self.model_manager.selected.as_ref() == Some(&name) {
+                    self.model_manager.selected = None;
+                    self.model_manager.show = None;
+                }
+                self.load_local_models();
+            }
+            (
+                ModelCall::Copy {
+                    source,
+                    destination,
+                    remove_source,
+                },
+                _,
+            ) => {
+                self.model_manager.error = None;
+                self.model_manager.copy_target.clear();
+                self.load_local_models();
+                if remove_source && self.selected_model == source {
+                    self.selected_model = destination.clone();
+                    self.save_settings();
                 }
+                self.call_models(ModelCall::Show(destination));
             }
-            Err(e) => eprintln!("Error loading models: {}", e),
+            (ModelCall::Running, ModelAnswer::Running(running)) => {
+                self.model_manager.running = running
+            }
+            (ModelCall::Unload(_), _) => self.load_running_models(),
+            _ => {}
+        }
+    }
+
+    fn copy_selected_model(&mut self, remove_source: bool) {
+        let Some(source) = self.model_manager.selected.clone() else {
+            return;
+        };
+        let destination = self.model_manager.copy_target.trim().to_string();
+        if destination.is_empty() || destination == source {
+            return;
         }
+        self.call_models(ModelCall::Copy {
+            source,
+            destination,
+            remove_source,
+        });
     }
 
+    #[tracing::instrument(level = "debug", skip_all)]
     pub fn save_settings(&self) {
         let path = PathBuf::from("./settings/settings.json");
+        let overrides = &self.overrides;
+        let active_server = SessionOverrides::saved(&overrides.server, self.active_server);
         let settings = AppSettings {
-            theme: format!("{:?}", self.theme),
-            default_url: self.default_url.clone(),
-            selected_model: self.selected_model.clone(),
+            theme: SessionOverrides::saved(&overrides.theme, theme_key(&self.theme)),
+            default_url: self.server_by_id(active_server).url.clone(),
+            selected_model: SessionOverrides::saved(&overrides.model, self.selected_model.clone()),
+            max_concurrent_downloads: self.max_concurrent_downloads,
+            servers: self
+                .servers
+                .iter()
+                .filter(|server| Some(server.id) != overrides.temporary_server)
+                .cloned()
+                .collect(),
+            active_server: Some(active_server),
+            auto_titles: self.auto_titles,
+            title_model: self.title_model.clone(),
+            send_key: self.send_key,
+            api_enabled: self.api_enabled,
+            api_port: self.api_port,
+            window: self.window.clone(),
+            sidebar_width: self.sidebar_width,
+            sidebar_collapsed: self.sidebar_collapsed,
+            follow_system_theme: self.follow_system_theme,
+            light_theme: theme_key(&self.light_theme),
+            dark_theme: theme_key(&self.dark_theme),
+            fonts: self.fonts.clone(),
+            zoom: self.zoom,
+            language: self.language,
         };
-        let _ = fs::write(path, serde_json::to_string_pretty(&settings).unwrap());
+        if let Err(e) = fs::write(path, serde_json::to_string_pretty(&settings).unwrap()) {
+            tracing::error!(error = %e, "could not save settings.json");
+        }
+        if let Err(e) = save_secrets(&self.servers) {
+            tracing::error!(error = %e, "could not save secrets.json");
+        }
     }
 
-    pub fn new() -> Self {
-        let settings = Self::load_settings();
-        let mut theme = IcedTheme::GruvboxDark;
+    fn load_downloads(active_server: Uuid) -> Vec<DownloadJob> {
+        fs::read_to_string("./settings/downloads.json")
+            .ok()
+            .and_then(|contents| serde_json::from_str::<Vec<PersistedDownload>>(&contents).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|dl| {
+                let server = dl.server.unwrap_or(active_server);
+                DownloadJob::new(dl.id, dl.model, server, dl.url)
+            })
+            .collect()
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    fn save_downloads(&self) {
+        let persisted: Vec<PersistedDownload> = self
+            .downloads
+            .iter()
+            .map(|dl| PersistedDownload {
+                id: dl.progress.id,
+                model: dl.progress.model.clone(),
+                url: dl.url.clone(),
+                server: Some(dl.server),
+            })
+            .collect();
+        let _ = fs::create_dir_all("./settings");
+        if let Err(e) = fs::write(
+            "./settings/downloads.json",
+            serde_json::to_string_pretty(&persisted).unwrap(),
+        ) {
+            tracing::error!(error = %e, "could not save downloads.json");
+        }
+    }
+
+    fn queue_download(&mut self, model: String) {
+        if !self.manages_models() {
+            return;
+        }
+        let url = self.server().endpoint("/api/pull");
+        self.downloads.push(DownloadJob::new(
+            Uuid::new_v4(),
+            model,
+            self.active_server,
+            url,
+        ));
+        self.schedule_downloads();
+        self.save_downloads();
+    }
 
-        for available in IcedTheme::ALL {
-            if format!("{:?}", available) == settings.theme {
-                theme = available.clone();
+    /// Starts queued pulls while fewer than `max_concurrent_downloads` are running.
+    fn schedule_downloads(&mut self) {
+        let mut active = self
+            .downloads
+            .iter()
+            .filter(|dl| dl.state == DownloadState::Active)
+            .count();
+        for dl in self.downloads.iter_mut() {
+            if active >= self.max_concurrent_downloads.max(1) {
                 break;
             }
+            if dl.state == DownloadState::Queued {
+                dl.state = DownloadState::Active;
+                dl.progress.status = tr("download-starting");
+                active += 1;
+            }
         }
+    }
 
-        let mut chats = Vec::new();
+    pub fn new() -> Self {
+        Self::with_options(&LaunchOptions::default())
+    }
 
-        if let Ok(entries) = fs::read_dir("./chats") {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.extension().map_or(false, |ext| ext == "json") {
-                    if let Ok(file) = fs::File::open(&path) {
-                        match serde_json::from_reader::<_, ChatHistory>(file) {
-                            Ok(history) => {
-                                if let Ok(chat) = OllamaChat::from_history(history) {
-                                    chats.push(chat);
-                                }
-                            }
-                            Err(e) => eprintln!("Error parsing chat file: {}", e),
-                        }
+    /// Opens with the command-line and environment settings in `options` taking
+    /// precedence over `settings.json`.
+    pub fn with_options(options: &LaunchOptions) -> Self {
+        let mut settings = Self::load_settings();
+        i18n::set_language(settings.language);
+
+        let mut active_server = settings.prepare_servers();
+        let mut overrides = SessionOverrides::default();
+        if let Some(url) = &options.server_url {
+            let server = match settings.servers.iter().find(|s| &s.url == url) {
+                Some(server) => server.id,
+                None => {
+                    let server = ServerProfile::new(&tr("server-command-line"), url);
+                    overrides.temporary_server = Some(server.id);
+                    settings.servers.push(server);
+                    settings.servers.last().unwrap().id
+                }
+            };
+            overrides.server = Some((server, active_server));
+            active_server = server;
+        }
+        if let Some(name) = &options.theme {
+            overrides.theme = Some((name.clone(), settings.theme.clone()));
+            settings.theme = name.clone();
+        }
+        if let Some(model) = &options.model {
+            overrides.model = Some((model.clone(), settings.selected_model.clone()));
+            settings.selected_model = model.clone();
+        }
+        if let Some(size) = options.window_size {
+            overrides.window_size = Some((size, settings.window.size()));
+        }
+        let mut client_errors = Vec::new();
+        let server_clients = settings
+            .servers
+            .iter()
+            .filter_map(|server| match server.client() {
+                Ok(client) => Some((server.id, client)),
+                Err(e) => {
+                    client_errors.push(format!("{}: {}", server.name, e));
+                    None
+                }
+            })
+            .collect();
+
+        let (custom_themes, theme_errors) = load_themes();
+        client_errors.extend(theme_errors);
+        let resolve = |key: &str| find_theme(key, &custom_themes).unwrap_or_else(default_theme);
+        let theme = resolve(&settings.theme);
+        let light_theme = resolve(&settings.light_theme);
+        let dark_theme = resolve(&settings.dark_theme);
+
+        let mut chats = OllamaChat::load_all();
+
+        // Chats saved before server profiles existed belong to the active server.
+        for chat in chats.iter_mut() {
+            if !settings.servers.iter().any(|s| s.id == chat.server) {
+                chat.server = active_server;
+            }
+        }
+
+        if chats.is_empty() {
+            chats.push(OllamaChat::new(
+                "llama3.2:latest".to_string(),
+                active_server,
+            ));
+        }
+
+        let (keymap, keymap_errors) = Keymap::load();
+        client_errors.extend(keymap_errors);
+
+        let mut gui = Self {
+            current_chat: chats[0].uuid,
+            chats,
+            editing_chat: None,
+            state: AppState::Chat,
+            server_draft: settings.servers[0].clone(),
+            server_headers: text_editor::Content::new(),
+            connection_test: None,
+            servers: settings.servers,
+            active_server,
+            server_clients,
+            health: HashMap::new(),
+            theme,
+            download_model_input: String::new(),
+            downloads: Self::load_downloads(active_server),
+            max_concurrent_downloads: settings.max_concurrent_downloads,
+            notice: (!client_errors.is_empty()).then(|| client_errors.join("; ")),
+            local_models: HashMap::new(),
+            model_manager: ModelManager::default(),
+            model_calls: Vec::new(),
+            model_editor: ModelEditor::default(),
+            library: LibraryBrowser::load(),
+            arena: Arena::default(),
+            auto_titles: settings.auto_titles,
+            title_model: settings.title_model,
+            send_key: settings.send_key,
+            prompts: PromptLibrary::load(),
+            prompt_form: None,
+            prompt_editor: PromptEditor::default(),
+            keymap,
+            palette: None,
+            api_enabled: settings.api_enabled,
+            api_port: settings.api_port,
+            api_port_input: settings.api_port.to_string(),
+            api_token: settings
+                .api_enabled
+                .then(|| automation::load_or_create_token().ok())
+                .flatten(),
+            api_error: None,
+            api_listeners: HashMap::new(),
+            chat_search: String::new(),
+            selected_model: settings.selected_model,
+            context_lengths: HashMap::new(),
+            context_length_attempts: HashMap::new(),
+            overrides,
+            window_size: options.window_size.unwrap_or(settings.window.size()),
+            window_position: None,
+            window: settings.window,
+            sidebar_width: settings.sidebar_width,
+            sidebar_collapsed: settings.sidebar_collapsed,
+            dragging_sidebar: false,
+            custom_themes,
+            follow_system_theme: settings.follow_system_theme,
+            light_theme,
+            dark_theme,
+            system_dark: settings.follow_system_theme && appearance::system_is_dark(),
+            theme_editor: None,
+            font_form: FontForm::new(&settings.fonts),
+            fonts: settings.fonts,
+            zoom: settings.zoom.clamp(MIN_ZOOM, MAX_ZOOM),
+            language: settings.language,
+        };
+        gui.edit_server(gui.server().clone());
+        gui.load_local_models();
+        gui.schedule_downloads();
+
+        if let Some(id) = options.open_chat {
+            if gui.chats.iter().any(|c| c.uuid == id) {
+                gui.current_chat = id;
+            } else {
+                gui.notice = Some(tr_args("chat-not-found", &[("id", id.to_string().into())]));
+            }
+        }
+        if let Some(prompt) = &options.new_chat {
+            gui.handle(Message::NewChat);
+            let id = gui.current_chat;
+            if let Some(chat) = gui.chats.iter_mut().find(|c| c.uuid == id) {
+                chat.set_input(prompt);
+            }
+            gui.handle(Message::StartChat(id));
+        }
+        gui
+    }
+
+    /// Maximizes the window if it was maximized when it last closed.
+    pub fn restore_window(&self) -> Task<Message> {
+        if self.window.maximized {
+            window::get_oldest().and_then(|id| window::maximize(id, true))
+        } else {
+            Task::none()
+        }
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        self.zoom as f64
+    }
+
+    fn theme_choices(&self) -> Vec<IcedTheme> {
+        IcedTheme::ALL
+            .iter()
+            .chain(&self.custom_themes)
+            .cloned()
+            .collect()
+    }
+
+    fn check_system_theme(&self) -> Task<Message> {
+        let current = self.system_dark;
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(appearance::system_is_dark)
+                    .await
+                    .unwrap_or(current)
+            },
+            Message::SystemThemeChecked,
+        )
+    }
+
+    /// Rereads `THEMES_DIR`, picking up edited palettes of the themes in use.
+    fn reload_themes(&mut self) {
+        let (custom_themes, errors) = load_themes();
+        self.custom_themes = custom_themes;
+        for theme in [&mut self.theme, &mut self.light_theme, &mut self.dark_theme] {
+            *theme =
+                find_theme(&theme_key(theme), &self.custom_themes).unwrap_or_else(default_theme);
+        }
+        if !errors.is_empty() {
+            self.notice = Some(errors.join("; "));
+        }
+    }
+
+    fn compact(&self) -> bool {
+        self.window_size.width < COMPACT_SIZE.width || self.window_size.height < COMPACT_SIZE.height
+    }
+
+    fn sidebar_width(&self) -> f32 {
+        let width = self
+            .sidebar_width
+            .min(self.window_size.width / 2.0)
+            .max(MIN_SIDEBAR_WIDTH);
+        if self.compact() {
+            width.min(COMPACT_SIDEBAR_WIDTH)
+        } else {
+            width
+        }
+    }
+
+    pub fn update(&mut self, message: Message) -> Task<Message> {
+        match message {
+            Message::KeyPressed(key, modifiers, captured) => {
+                self.key_pressed(key, modifiers, captured)
+            }
+            Message::PaletteSelect(index) => {
+                let Some(palette) = self.palette.take() else {
+                    return Task::none();
+                };
+                match self.palette_entries(&palette.query).into_iter().nth(index) {
+                    Some(PaletteEntry::Action(action)) => self.run_action(action),
+                    Some(PaletteEntry::Chat(id, _)) => {
+                        self.handle(Message::ChangeAppState(AppState::Chat));
+                        self.handle(Message::SelectChat(id));
+                        Task::none()
+                    }
+                    Some(PaletteEntry::Model(model)) => {
+                        self.handle(Message::ChangeAppState(AppState::Chat));
+                        self.handle(Message::ChangeChatModel(self.current_chat, model));
+                        Task::none()
+                    }
+                    None => Task::none(),
+                }
+            }
+            Message::WindowCloseRequested(id) => {
+                window::get_maximized(id).map(move |maximized| Message::CloseWindow(id, maximized))
+            }
+            Message::CloseWindow(id, maximized) => {
+                // A maximized window keeps the size and place it had before.
+                self.window.maximized = maximized;
+                if !maximized {
+                    let size =
+                        SessionOverrides::saved(&self.overrides.window_size, self.window_size);
+                    self.window.width = size.width;
+                    self.window.height = size.height;
+                    if let Some(position) = self.window_position {
+                        self.window.position = Some((position.x, position.y));
+                    }
+                }
+                self.save_settings();
+                window::close(id)
+            }
+            Message::FollowSystemTheme(follow) => {
+                self.follow_system_theme = follow;
+                self.save_settings();
+                if follow {
+                    self.check_system_theme()
+                } else {
+                    Task::none()
+                }
+            }
+            Message::CheckSystemTheme => self.check_system_theme(),
+            Message::CopyDiagnostics => {
+                self.notice = Some(tr("diagnostics-copied"));
+                iced::clipboard::write(self.diagnostics_bundle())
+            }
+            message => {
+                self.handle(message);
+                Task::none()
+            }
+        }
+    }
+
+    fn key_pressed(
+        &mut self,
+        key: keyboard::Key,
+        modifiers: keyboard::Modifiers,
+        captured: bool,
+    ) -> Task<Message> {
+        // While the palette is open, arrows move the highlight and Escape closes it.
+        if let Some(query) = self.palette.as_ref().map(|palette| palette.query.clone()) {
+            let count = self.palette_entries(&query).len();
+            let palette = self.palette.as_mut().unwrap();
+            match key.as_ref() {
+                keyboard::Key::Named(keyboard::key::Named::Escape) => {
+                    self.palette = None;
+                    return Task::none();
+                }
+                keyboard::Key::Named(keyboard::key::Named::ArrowDown) => {
+                    palette.selected = (palette.selected + 1).min(count.saturating_sub(1));
+                    return Task::none();
+                }
+                keyboard::Key::Named(keyboard::key::Named::ArrowUp) => {
+                    palette.selected = palette.selected.saturating_sub(1);
+                    return Task::none();
+                }
+                _ => {}
+            }
+        }
+        match self.keymap.action(&key, modifiers, captured) {
+            Some(action) => self.run_action(action),
+            None => Task::none(),
+        }
+    }
+
+    fn run_action(&mut self, action: Action) -> Task<Message> {
+        match action {
+            Action::NewChat => {
+                self.handle(Message::ChangeAppState(AppState::Chat));
+                self.handle(Message::NewChat);
+                focus_prompt_input()
+            }
+            Action::NextChat | Action::PreviousChat => {
+                let count = self.chats.len();
+                if let Some(index) = self.chats.iter().position(|c| c.uuid == self.current_chat) {
+                    let next = match action {
+                        Action::NextChat => (index + 1) % count,
+                        _ => (index + count - 1) % count,
+                    };
+                    self.handle(Message::SelectChat(self.chats[next].uuid));
+                }
+                self.handle(Message::ChangeAppState(AppState::Chat));
+                Task::none()
+            }
+            Action::FocusInput => {
+                self.handle(Message::ChangeAppState(AppState::Chat));
+                focus_prompt_input()
+            }
+            Action::StopGeneration => {
+                self.handle(Message::StopChat(self.current_chat));
+                Task::none()
+            }
+            Action::Regenerate => {
+                self.handle(Message::RegenerateResponse(self.current_chat));
+                Task::none()
+            }
+            Action::OpenSettings => {
+                self.handle(Message::ChangeAppState(AppState::Settings));
+                Task::none()
+            }
+            Action::Search => {
+                self.handle(Message::ChangeAppState(AppState::Chat));
+                self.sidebar_collapsed = false;
+                text_input::focus(text_input::Id::new(CHAT_SEARCH))
+            }
+            Action::ToggleSidebar => {
+                self.handle(Message::ChangeAppState(AppState::Chat));
+                self.handle(Message::ToggleSidebar);
+                Task::none()
+            }
+            Action::ZoomIn | Action::ZoomOut | Action::ResetZoom => {
+                let zoom = match action {
+                    Action::ZoomIn => self.zoom + ZOOM_STEP,
+                    Action::ZoomOut => self.zoom - ZOOM_STEP,
+                    _ => default_zoom(),
+                };
+                self.handle(Message::Zoom(zoom));
+                Task::none()
+            }
+            Action::CommandPalette => {
+                if self.palette.take().is_some() {
+                    return Task::none();
+                }
+                self.palette = Some(CommandPalette::default());
+                text_input::focus(text_input::Id::new(PALETTE_INPUT))
+            }
+        }
+    }
+
+    fn handle_api_request(&mut self, request: ApiRequest) {
+        match request {
+            ApiRequest::ListChats(reply) => {
+                let chats: Vec<_> = self
+                    .chats
+                    .iter()
+                    .map(|chat| {
+                        json!({
+                            "uuid": chat.uuid,
+                            "name": chat.display_name,
+                            "model": chat.model,
+                            "turns": chat.chat_entries.len(),
+                            "busy": matches!(chat.state, ChatState::Streaming | ChatState::Queued),
+                        })
+                    })
+                    .collect();
+                let _ = reply.unbounded_send(ApiResponse::Json(json!(chats)));
+            }
+            ApiRequest::CreateChat { name, model, reply } => {
+                let mut chat = OllamaChat::new(
+                    model.unwrap_or_else(|| self.selected_model.clone()),
+                    self.active_server,
+                );
+                if let Some(name) = name.filter(|name| !name.trim().is_empty()) {
+                    chat.start_rename();
+                    chat.update_temp_name(name);
+                    chat.finish_rename();
+                }
+                let _ = reply.unbounded_send(ApiResponse::Json(json!({
+                    "uuid": chat.uuid,
+                    "name": chat.display_name,
+                    "model": chat.model,
+                })));
+                self.chats.push(chat);
+            }
+            ApiRequest::SendPrompt {
+                chat,
+                prompt,
+                reply,
+            } => {
+                let Some(index) = self.chats.iter().position(|c| {
+                    c.uuid.to_string() == chat.to_lowercase()
+                        || c.display_name.eq_ignore_ascii_case(&chat)
+                }) else {
+                    let _ = reply.unbounded_send(ApiResponse::Error(
+                        404,
+                        format!("no chat matches {}", chat),
+                    ));
+                    return;
+                };
+                let offline = self.health(self.chats[index].server).is_offline();
+                let chat = &mut self.chats[index];
+                if offline
+                    || chat.summarizing.is_some()
+                    || matches!(chat.state, ChatState::Streaming | ChatState::Queued)
+                {
+                    let reason = if offline {
+                        "the server is offline"
+                    } else {
+                        "the chat is busy"
+                    };
+                    let _ = reply.unbounded_send(ApiResponse::Error(409, reason.to_string()));
+                    return;
+                }
+                chat.send(prompt);
+                let _ = reply.unbounded_send(ApiResponse::Line(json!({
+                    "chat": chat.uuid,
+                    "model": chat.model,
+                })));
+                self.api_listeners.entry(chat.uuid).or_default().push(reply);
+            }
+            ApiRequest::Failed(e) => self.api_error = Some(e),
+        }
+    }
+
+    /// Actions, chats and models matching `query`, best match first.
+    fn palette_entries(&self, query: &str) -> Vec<PaletteEntry> {
+        let entries = Action::ALL
+            .into_iter()
+            .filter(|action| *action != Action::CommandPalette)
+            .map(PaletteEntry::Action)
+            .chain(
+                self.chats
+                    .iter()
+                    .map(|chat| PaletteEntry::Chat(chat.uuid, chat.display_name.clone())),
+            )
+            .chain(self.model_names().into_iter().map(PaletteEntry::Model));
+        let mut scored: Vec<(i32, PaletteEntry)> = entries
+            .filter_map(|entry| Some((fuzzy_score(query, &entry.to_string())?, entry)))
+            .collect();
+        if !query.trim().is_empty() {
+            scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        }
+        scored
+            .into_iter()
+            .map(|(_, entry)| entry)
+            .take(MAX_PALETTE_ENTRIES)
+            .collect()
+    }
+
+    fn handle(&mut self, message: Message) {
+        match message {
+            Message::NewChat => {
+                let new_chat = OllamaChat::new(self.selected_model.clone(), self.active_server);
+                self.current_chat = new_chat.uuid;
+                self.chats.push(new_chat);
+            }
+            Message::StartChat(id) => {
+                let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) else {
+                    return;
+                };
+                // `/command rest` expands a template instead of sending.
+                let input = chat.input_text();
+                if let Some(input) = input.strip_prefix('/') {
+                    let (command, rest) =
+                        input.split_once(char::is_whitespace).unwrap_or((input, ""));
+                    if let Some(template) = self.prompts.find_command(command).cloned() {
+                        let rest = rest.trim().to_string();
+                        self.insert_prompt(id, template, rest);
+                        return;
+                    }
+                }
+                chat.start();
+            }
+            Message::InsertPrompt(id, template) => self.insert_prompt(id, template, String::new()),
+            Message::PromptFormChanged(index, value) => {
+                if let Some((_, v)) = self
+                    .prompt_form
+                    .as_mut()
+                    .and_then(|form| form.values.get_mut(index))
+                {
+                    *v = value;
+                }
+            }
+            Message::SubmitPromptForm(send) => {
+                let Some(form) = self.prompt_form.take() else {
+                    return;
+                };
+                let values = form.values.into_iter().collect();
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == form.chat) {
+                    chat.set_input(&form.template.fill(&values));
+                    if send {
+                        chat.start();
+                    }
+                }
+            }
+            Message::CancelPromptForm => self.prompt_form = None,
+            Message::EditPromptTemplate(template) => {
+                self.prompt_editor = PromptEditor {
+                    original: Some(template.name.clone()),
+                    name: template.name,
+                    command: template.command,
+                    body: text_editor::Content::with_text(&template.body),
+                    path: std::mem::take(&mut self.prompt_editor.path),
+                    status: None,
+                }
+            }
+            Message::NewPromptTemplate => {
+                self.prompt_editor = PromptEditor {
+                    path: std::mem::take(&mut self.prompt_editor.path),
+                    ..PromptEditor::default()
+                }
+            }
+            Message::PromptTemplateNameChanged(name) => self.prompt_editor.name = name,
+            Message::PromptTemplateCommandChanged(command) => {
+                self.prompt_editor.command = command
+                    .trim_start_matches('/')
+                    .chars()
+                    .filter(|c| !c.is_whitespace())
+                    .collect()
+            }
+            Message::PromptTemplateBodyAction(action) => self.prompt_editor.body.perform(action),
+            Message::SavePromptTemplate => {
+                let editor = &mut self.prompt_editor;
+                let template = PromptTemplate {
+                    name: editor.name.trim().to_string(),
+                    command: editor.command.clone(),
+                    body: editor.body.text().trim_end().to_string(),
+                };
+                if template.name.is_empty() || template.body.is_empty() {
+                    return;
+                }
+                if let Some(original) = editor.original.take() {
+                    self.prompts.templates.retain(|t| t.name != original);
+                }
+                editor.original = Some(template.name.clone());
+                editor.status = Some(tr_args(
+                    "prompts-saved",
+                    &[("name", template.name.as_str().into())],
+                ));
+                self.prompts.upsert(template);
+                self.prompts.save();
+            }
+            Message::DeletePromptTemplate(name) => {
+                self.prompts.templates.retain(|t| t.name != name);
+                if self.prompt_editor.original.as_ref() == Some(&name) {
+                    self.prompt_editor.original = None;
+                }
+                self.prompts.save();
+            }
+            Message::PromptLibraryPathChanged(path) => self.prompt_editor.path = path,
+            Message::ImportPrompts => {
+                self.prompt_editor.status =
+                    Some(match self.prompts.import(&self.prompt_editor.path) {
+                        Ok(count) => {
+                            self.prompts.save();
+                            tr_args("prompts-imported", &[("count", count.into())])
+                        }
+                        Err(e) => tr_args("error-message", &[("error", e.to_string().into())]),
+                    });
+            }
+            Message::ExportPrompts => {
+                self.prompt_editor.status =
+                    Some(match self.prompts.export(&self.prompt_editor.path) {
+                        Ok(()) => tr_args(
+                            "prompts-exported",
+                            &[("count", self.prompts.templates.len().into())],
+                        ),
+                        Err(e) => tr_args("error-message", &[("error", e.to_string().into())]),
+                    });
+            }
+            Message::ChatProgress((id, progress)) => {
+                let line = match &progress {
+                    Ok(OllamaStreamProgress::Streaming { token }) => json!({ "token": token }),
+                    Ok(OllamaStreamProgress::Finished { stats, .. }) => {
+                        json!({ "done": true, "stats": stats })
+                    }
+                    Err(e) => json!({ "error": e.to_string() }),
+                };
+                let error = progress.as_ref().err().cloned();
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
+                    chat.progress(progress);
+                    // A queued prompt is sent again later, so its listeners keep waiting.
+                    let (line, done) = match chat.state {
+                        ChatState::Streaming => (line, false),
+                        ChatState::Queued => (json!({ "status": "queued" }), false),
+                        _ => (line, true),
+                    };
+                    notify_api(&mut self.api_listeners, id, line, done);
+                    // Title the chat once its first exchange is complete.
+                    if self.auto_titles
+                        && !chat.renamed
+                        && chat.chat_entries.len() == 1
+                        && matches!(chat.state, ChatState::Finished)
+                    {
+                        chat.titling = true;
+                    }
+                    // The badge should not keep saying connected, and the queued prompt
+                    // goes out again when a later check succeeds.
+                    if let (ChatState::Queued, Some(error)) = (&chat.state, error) {
+                        let server = chat.server;
+                        self.record_health(server, Err(error));
+                    }
+                }
+            }
+            Message::RegenerateTitle(id) => {
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
+                    if !chat.chat_entries.is_empty() {
+                        chat.renamed = false;
+                        chat.titling = true;
+                    }
+                }
+            }
+            Message::TitleGenerated(id, result) => {
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
+                    chat.titling = false;
+                    if let Some(title) = result.ok().and_then(|title| clean_title(&title)) {
+                        // The user may have renamed the chat while the title was generated.
+                        if !chat.renamed {
+                            chat.display_name = title;
+                            chat.save_chat_history();
+                        }
+                    }
+                }
+            }
+            Message::ToggleAutoTitles(enabled) => {
+                self.auto_titles = enabled;
+                self.save_settings();
+            }
+            Message::ChangeTitleModel(model) => {
+                self.title_model = Some(model).filter(|m| *m != tr("settings-same-as-chat"));
+                self.save_settings();
+            }
+            Message::ChangeChatModel(id, model) => {
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
+                    chat.change_model(model);
+                }
+            }
+            Message::CancelQueuedPrompt(id) => {
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
+                    chat.cancel_queued();
+                }
+            }
+            Message::HealthChecked(server, result) => self.record_health(server, result),
+            Message::SelectChat(id) => {
+                self.current_chat = id;
+                self.editing_chat = None;
+            }
+            Message::StopChat(id) => {
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
+                    chat.stop();
+                    notify_api(
+                        &mut self.api_listeners,
+                        id,
+                        json!({ "done": true, "stopped": true }),
+                        true,
+                    );
+                }
+            }
+            Message::RegenerateResponse(id) => {
+                let offline = self
+                    .chats
+                    .iter()
+                    .find(|c| c.uuid == id)
+                    .is_some_and(|chat| self.health(chat.server).is_offline());
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
+                    if !offline {
+                        chat.regenerate();
+                    }
+                }
+            }
+            Message::ChatSearchChanged(search) => self.chat_search = search,
+            Message::PaletteQueryChanged(query) => {
+                if let Some(palette) = &mut self.palette {
+                    palette.query = query;
+                    palette.selected = 0;
+                }
+            }
+            Message::ClosePalette => self.palette = None,
+            Message::Api(request) => self.handle_api_request(request),
+            Message::ToggleApi(enabled) => {
+                self.api_enabled = enabled;
+                self.api_error = None;
+                if enabled && self.api_token.is_none() {
+                    self.api_token = automation::load_or_create_token().ok();
+                }
+                self.save_settings();
+            }
+            Message::ChangeApiPort(port) => {
+                if port.chars().all(|c| c.is_ascii_digit()) {
+                    self.api_port_input = port;
+                }
+            }
+            Message::ApplyApiPort => match self.api_port_input.parse::<u16>() {
+                Ok(port) if port >= 1024 => {
+                    if port != self.api_port {
+                        self.api_port = port;
+                        self.api_error = None;
+                        self.save_settings();
+                    }
+                }
+                _ => self.api_error = Some(tr("api-port-invalid")),
+            },
+            Message::KeyPressed(..)
+            | Message::PaletteSelect(_)
+            | Message::CopyDiagnostics
+            | Message::WindowCloseRequested(_)
+            | Message::CloseWindow(..)
+            | Message::FollowSystemTheme(_)
+            | Message::CheckSystemTheme => {}
+            Message::SystemThemeChecked(dark) => self.system_dark = dark,
+            Message::ChangeLightTheme(theme) => {
+                self.light_theme = theme;
+                self.save_settings();
+            }
+            Message::ChangeDarkTheme(theme) => {
+                self.dark_theme = theme;
+                self.save_settings();
+            }
+            Message::EditTheme => {
+                let current = self.theme();
+                // Editing a custom theme keeps its name, so saving updates its file.
+                let name = match &current {
+                    IcedTheme::Custom(custom) => custom.to_string(),
+                    builtin => tr_args(
+                        "appearance-custom-theme",
+                        &[("theme", builtin.to_string().into())],
+                    ),
+                };
+                self.theme_editor = Some(ThemeFile::from_theme(name, &current));
+            }
+            Message::ThemeEditorName(name) => {
+                if let Some(editor) = &mut self.theme_editor {
+                    editor.name = name;
+                }
+            }
+            Message::ThemeEditorColor(color, value) => {
+                if let Some(editor) = &mut self.theme_editor {
+                    editor.set_color(color, value);
+                }
+            }
+            Message::SaveTheme => {
+                if let Some(editor) = &self.theme_editor {
+                    match editor.save() {
+                        Ok(path) => {
+                            let key = editor.name.trim().to_string();
+                            self.theme_editor = None;
+                            self.reload_themes();
+                            if let Some(saved) = find_theme(&key, &self.custom_themes) {
+                                match (self.follow_system_theme, self.system_dark) {
+                                    (true, true) => self.dark_theme = saved,
+                                    (true, false) => self.light_theme = saved,
+                                    (false, _) => self.theme = saved,
+                                }
+                            }
+                            self.save_settings();
+                            self.notice = Some(tr_args(
+                                "appearance-theme-saved",
+                                &[("path", path.display().to_string().into())],
+                            ));
+                        }
+                        Err(e) => {
+                            self.notice = Some(tr_args(
+                                "appearance-theme-save-failed",
+                                &[("error", e.to_string().into())],
+                            ))
+                        }
+                    }
+                }
+            }
+            Message::CancelThemeEdit => self.theme_editor = None,
+            Message::ReloadThemes => {
+                self.reload_themes();
+                self.save_settings();
+            }
+            Message::ChangeUiFont(family) => self.font_form.ui = family,
+            Message::ChangeMonospaceFont(family) => self.font_form.monospace = family,
+            Message::ChangeTextSize(size) => self.font_form.size = size,
+            Message::ApplyFonts => match self.font_form.size.trim().parse::<f32>() {
+                Ok(size) => {
+                    let fonts = FontSettings {
+                        ui: self.font_form.ui.trim().to_string(),
+                        monospace: self.font_form.monospace.trim().to_string(),
+                        size: size.clamp(MIN_TEXT_SIZE, MAX_TEXT_SIZE),
+                    };
+                    if fonts.ui != self.fonts.ui || fonts.size != self.fonts.size {
+                        self.notice = Some(tr("appearance-restart-needed"));
+                    }
+                    self.font_form = FontForm::new(&fonts);
+                    self.fonts = fonts;
+                    self.save_settings();
+                }
+                Err(_) => self.notice = Some(tr("appearance-size-invalid")),
+            },
+            Message::ChangeLanguage(language) => {
+                i18n::set_language(language);
+                self.language = language;
+                self.save_settings();
+            }
+            Message::Zoom(zoom) => {
+                // Rounded so repeated steps land on whole percentages.
+                self.zoom = ((zoom * 100.0).round() / 100.0).clamp(MIN_ZOOM, MAX_ZOOM);
+                self.save_settings();
+            }
+            Message::WindowResized(size) => self.window_size = size,
+            Message::WindowMoved(position) => {
+                // Windows reports minimized windows at -32000.
+                if position.x > -16000.0 && position.y > -16000.0 {
+                    self.window_position = Some(position);
+                }
+            }
+            Message::ToggleSidebar => {
+                self.sidebar_collapsed = !self.sidebar_collapsed;
+                self.save_settings();
+            }
+            Message::SidebarDragStarted => self.dragging_sidebar = true,
+            Message::SidebarDragged(x) => {
+                // The page has 5 pixels of padding left of the sidebar.
+                self.sidebar_width = (x - 5.0)
+                    .min(self.window_size.width / 2.0)
+                    .max(MIN_SIDEBAR_WIDTH);
+            }
+            Message::SidebarDragEnded => {
+                self.dragging_sidebar = false;
+                self.save_settings();
+            }
+            Message::PromptAction(id, action) => {
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
+                    chat.draft_dirty |= action.is_edit();
+                    chat.input.perform(action);
+                }
+            }
+            Message::SaveDrafts => {
+                for chat in self.chats.iter_mut().filter(|c| c.draft_dirty) {
+                    chat.save_chat_history();
+                }
+            }
+            Message::ChangeSendKey(send_key) => {
+                self.send_key = send_key;
+                self.save_settings();
+            }
+            Message::StartRenameChat(uuid) => {
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == uuid) {
+                    chat.start_rename();
+                    self.editing_chat = Some(uuid);
+                }
+            }
+            Message::UpdateTempName(uuid, name) => {
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == uuid) {
+                    chat.update_temp_name(name);
+                }
+            }
+            Message::FinishRenameChat(uuid) => {
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == uuid) {
+                    chat.finish_rename();
+                    self.editing_chat = None;
+                }
+            }
+            Message::CancelRenameChat(uuid) => {
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == uuid) {
+                    chat.cancel_rename();
+                    self.editing_chat = None;
+                }
+            }
+            Message::DeleteChat(uuid) => {
+                notify_api(
+                    &mut self.api_listeners,
+                    uuid,
+                    json!({ "error": "the chat was deleted" }),
+                    true,
+                );
+                self.chats.retain(|c| c.uuid != uuid);
+                if self.current_chat == uuid {
+                    self.current_chat = self.chats.first().map(|c| c.uuid).unwrap_or(Uuid::nil());
+                }
+                match fs::remove_file(format!("./chats/{}.json", uuid)) {
+                    Ok(()) => tracing::info!(chat = %uuid, "deleted chat"),
+                    // Chats are only written once they have a turn.
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => tracing::error!(chat = %uuid, error = %e, "could not delete chat"),
+                }
+            }
+            Message::ChangeAppState(app_state) => {
+                if app_state.needs_model_management() && !self.manages_models() {
+                    return;
+                }
+                match app_state {
+                    AppState::Models => self.load_running_models(),
+                    AppState::Library => {
+                        self.library.free_space = available_model_space(&self.server().url)
+                    }
+                    _ => {}
+                }
+                self.state = app_state;
+            }
+            Message::ChangeTheme(theme) => {
+                self.theme = theme;
+                self.save_settings();
+            }
+            Message::SelectServer(server) => {
+                self.active_server = server.id;
+                self.edit_server(server);
+                if !self.local_models.contains_key(&self.active_server) {
+                    self.load_local_models();
+                }
+                self.model_manager = ModelManager::default();
+                self.leave_unsupported_view();
+                self.save_settings();
+            }
+            Message::EditServer(server) => self.edit_server(server),
+            Message::NewServer => self.edit_server(ServerProfile::new(
+                &tr("server-new"),
+                "http://localhost:11434",
+            )),
+            Message::ServerNameChanged(name) => self.server_draft.name = name,
+            Message::ChangeDefaultUrl(url) => {
+                self.server_draft.url = url;
+                self.connection_test = None;
+            }
+            Message::ServerBackendChanged(backend) => {
+                self.server_draft.backend = backend;
+                self.connection_test = None;
+            }
+            Message::ServerAuthKindChanged(auth) => self.server_draft.auth = auth,
+            Message::ServerUsernameChanged(username) => self.server_draft.username = username,
+            Message::ServerPasswordChanged(password) => {
+                self.server_draft.secrets.password = password
+            }
+            Message::ServerTokenChanged(token) => self.server_draft.secrets.token = token,
+            Message::ServerHeadersAction(action) => self.server_headers.perform(action),
+            Message::ServerCaCertChanged(path) => {
+                self.server_draft.ca_cert =
+                    Some(PathBuf::from(path.trim())).filter(|p| !p.as_os_str().is_empty())
+            }
+            Message::ServerIdentityChanged(path) => {
+                self.server_draft.client_identity =
+                    Some(PathBuf::from(path.trim())).filter(|p| !p.as_os_str().is_empty())
+            }
+            Message::ServerInsecureToggled(insecure) => {
+                self.server_draft.accept_invalid_certs = insecure
+            }
+            Message::ServerTimeoutChanged(timeout) => {
+                let digits: String = timeout.chars().filter(|c| c.is_ascii_digit()).collect();
+                self.server_draft.timeout_secs = digits.parse().ok().filter(|t| *t > 0);
+            }
+            Message::TestConnection => {
+                if normalize_url(&self.server_draft.url).is_ok() {
+                    self.connection_test = Some(ConnectionTest::Running(Uuid::new_v4()));
+                }
+            }
+            Message::ConnectionTested(id, result) => {
+                if let Some(ConnectionTest::Running(running)) = self.connection_test {
+                    if running == id {
+                        self.connection_test = Some(match result {
+                            Ok(version) => ConnectionTest::Succeeded(version),
+                            Err(e) => ConnectionTest::Failed(e.to_string()),
+                        });
+                    }
+                }
+            }
+            Message::SaveServer => {
+                self.server_draft.secrets.headers = parse_headers(&self.server_headers.text());
+                match normalize_url(&self.server_draft.url) {
+                    Ok(url) => self.server_draft.url = url,
+                    Err(e) => {
+                        self.notice = Some(e);
+                        return;
+                    }
+                }
+                let server = self.server_draft.clone();
+                let client = match server.client() {
+                    Ok(client) => client,
+                    Err(e) => {
+                        self.notice = Some(format!("{}: {}", server.name, e));
+                        return;
+                    }
+                };
+                match self.servers.iter_mut().find(|s| s.id == server.id) {
+                    Some(existing) => *existing = server.clone(),
+                    None => self.servers.push(server.clone()),
+                }
+                self.server_clients.insert(server.id, client);
+                self.local_models.remove(&server.id);
+                if self.overrides.temporary_server == Some(server.id) {
+                    self.overrides.temporary_server = None;
+                }
+                if server.id == self.active_server {
+                    self.load_local_models();
+                    self.leave_unsupported_view();
+                }
+                self.save_settings();
+            }
+            Message::DeleteServer(id) => {
+                if self.servers.len() > 1 {
+                    self.servers.retain(|s| s.id != id);
+                    self.server_clients.remove(&id);
+                    self.local_models.remove(&id);
+                    if self.active_server == id {
+                        self.active_server = self.servers[0].id;
+                        self.load_local_models();
+                        self.leave_unsupported_view();
+                    }
+                    if self.server_draft.id == id {
+                        self.edit_server(self.server().clone());
+                    }
+                    self.save_settings();
+                }
+            }
+            Message::DownloadModelInputChanged(input) => {
+                self.download_model_input = input;
+            }
+            Message::StartDownloadModel => {
+                let model = self.download_model_input.trim().to_string();
+                if !model.is_empty() {
+                    self.queue_download(model);
+                    self.download_model_input.clear();
+                }
+            }
+            Message::DownloadProgress(id, result) => match result {
+                Ok(DownloadProgressUpdate::Progress {
+                    status,
+                    digest,
+                    total,
+                    completed,
+                }) => {
+                    if let Some(dl) = self.downloads.iter_mut().find(|d| d.progress.id == id) {
+                        dl.update(status, digest, total, completed);
+                    }
+                }
+                Ok(DownloadProgressUpdate::Finished) => {
+                    if let Some(index) = self.downloads.iter().position(|d| d.progress.id == id) {
+                        let dl = self.downloads.remove(index);
+                        self.notice = Some(tr_args(
+                            "download-finished",
+                            &[("model", dl.progress.model.as_str().into())],
+                        ));
+                        self.load_local_models();
+                    }
+                    self.schedule_downloads();
+                    self.save_downloads();
+                }
+                Err(e) => {
+                    if let Some(dl) = self.downloads.iter_mut().find(|d| d.progress.id == id) {
+                        dl.attempts += 1;
+                        dl.speed = 0.0;
+                        dl.last_sample = None;
+                        if dl.attempts >= MAX_DOWNLOAD_ATTEMPTS {
+                            dl.state = DownloadState::Failed;
+                            dl.progress.status =
+                                tr_args("error-message", &[("error", e.to_string().into())]);
+                        } else {
+                            dl.progress.status = tr_args(
+                                "download-retrying",
+                                &[
+                                    ("seconds", retry_delay(dl.attempts).as_secs().into()),
+                                    ("attempt", (dl.attempts + 1).into()),
+                                    ("attempts", MAX_DOWNLOAD_ATTEMPTS.into()),
+                                    ("error", e.to_string().into()),
+                                ],
+                            );
+                        }
+                    }
+                    self.schedule_downloads();
+                }
+            },
+            Message::CancelDownload(id) => {
+                self.downloads.retain(|dl| dl.progress.id != id);
+                self.schedule_downloads();
+                self.save_downloads();
+            }
+            Message::RetryDownload(id) => {
+                if let Some(dl) = self.downloads.iter_mut().find(|d| d.progress.id == id) {
+                    dl.state = DownloadState::Queued;
+                    dl.attempts = 0;
+                    dl.progress.status = tr("download-queued");
+                }
+                self.schedule_downloads();
+            }
+            Message::ChangeMaxDownloads(max) => {
+                self.max_concurrent_downloads = max;
+                self.save_settings();
+                self.schedule_downloads();
+            }
+            Message::DismissNotice => self.notice = None,
+            Message::LibrarySearchChanged(search) => self.library.search = search,
+            Message::LibraryImportPathChanged(path) => self.library.import_path = path,
+            Message::ImportLibrary => self.library.import(),
+            Message::PullLibraryModel(model) => {
+                if !self.downloads.iter().any(|dl| dl.progress.model == model) {
+                    self.queue_download(model);
+                }
+            }
+            Message::ChangeSelectedModel(model) => {
+                self.selected_model = model;
+                self.save_settings();
+            }
+
+            Message::RefreshModels => {
+                self.load_local_models();
+            }
+            Message::ContextLengthLoaded(model, result) => match result {
+                Ok(length) => {
+                    self.context_length_attempts.remove(&model);
+                    self.context_lengths.insert(model, length);
+                }
+                Err(e) => {
+                    tracing::error!(server = %model.0, model = model.1, error = ?e, "could not read context length");
+                    *self.context_length_attempts.entry(model).or_default() += 1;
+                }
+            },
+            Message::ChangeNumCtx(id, value) => {
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
+                    chat.edit_num_ctx(value);
+                }
+            }
+            Message::SubmitNumCtx(id) => {
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
+                    chat.finish_num_ctx();
+                }
+            }
+            Message::DropOldestTurn(id) => {
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
+                    chat.drop_oldest_turn();
+                }
+            }
+            Message::SummarizeHistory(id) => {
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
+                    chat.start_summary(SummaryTarget::TrimHistory);
+                }
+            }
+            Message::ContinueInNewChat(id) => {
+                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
+                    chat.start_summary(SummaryTarget::NewChat);
+                }
+            }
+            Message::ModelFilterChanged(filter) => self.model_manager.filter = filter,
+            Message::ModelSortChanged(sort) => self.model_manager.sort = sort,
+            Message::SelectManagedModel(name) => self.call_models(ModelCall::Show(name)),
+            Message::ModelCallFinished(id, result) => self.model_call_finished(id, result),
+            Message::CopyTargetChanged(target) => self.model_manager.copy_target = target,
+            Message::CopyModel => self.copy_selected_model(false),
+            Message::RenameModel => self.copy_selected_model(true),
+            Message::DeleteModel(name) => self.model_manager.confirm_delete = Some(name),
+            Message::ConfirmDeleteModel(name) => {
+                self.call_models(ModelCall::Delete(name));
+                self.model_manager.confirm_delete = None;
+            }
+            Message::NewCustomModel => {
+                self.model_editor = ModelEditor {
+                    creating: std::mem::take(&mut self.model_editor.creating),
+                    from: Some(self.selected_model.clone()),
+                    ..ModelEditor::default()
+                };
+                self.state = AppState::ModelEditor;
+            }
+            Message::EditModel(name) => self.call_models(ModelCall::Edit(name)),
+            Message::ModelEditorNameChanged(name) => self.model_editor.name = name,
+            Message::ModelEditorFromChanged(from) => self.model_editor.from = Some(from),
+            Message::ModelEditorSystemAction(action) => self.model_editor.system.perform(action),
+            Message::ModelEditorTemplateAction(action) => {
+                self.model_editor.template.perform(action)
+            }
+            Message::ModelEditorParametersAction(action) => {
+                self.model_editor.parameters.perform(action)
+            }
+            Message::CreateModel => {
+                if self.manages_models() && self.model_editor.errors().is_empty() {
+                    let job = CreateJob {
+                        progress: DownloadProgress {
+                            id: Uuid::new_v4(),
+                            model: self.model_editor.name.trim().to_string(),
+                            status: tr("model-editor-starting"),
+                            total: 0,
+                            completed: 0,
+                        },
+                        server: self.active_server,
+                        body: self.model_editor.create_body(),
+                    };
+                    self.model_editor.creating.push(job);
+                }
+            }
+            Message::CreateProgress(id, result) => match result {
+                Ok(DownloadProgressUpdate::Progress {
+                    status,
+                    total,
+                    completed,
+                    ..
+                }) => {
+                    if let Some(job) = self.create_job(id) {
+                        job.status = status;
+                        job.total = total;
+                        job.completed = completed;
+                    }
+                }
+                Ok(DownloadProgressUpdate::Finished) => {
+                    self.model_editor
+                        .creating
+                        .retain(|job| job.progress.id != id);
+                    self.load_local_models();
+                }
+                Err(e) => {
+                    if let Some(job) = self.create_job(id) {
+                        job.status = tr_args("error-message", &[("error", e.to_string().into())]);
+                    }
+                }
+            },
+            Message::DismissCreate(id) => self
+                .model_editor
+                .creating
+                .retain(|job| job.progress.id != id),
+            Message::RefreshRunningModels => self.load_running_models(),
+            Message::UnloadModel(name) => self.call_models(ModelCall::Unload(name)),
+            Message::ArenaToggleModel(model, selected) => {
+                if !selected {
+                    self.arena.selected.retain(|m| *m != model);
+                } else if self.arena.selected.len() < MAX_ARENA_MODELS {
+                    self.arena.selected.push(model);
+                }
+            }
+            Message::ArenaPromptChanged(prompt) => self.arena.prompt = prompt,
+            Message::StartArena => {
+                if self.arena.can_start() {
+                    self.arena.start(self.active_server);
+                }
+            }
+            Message::ArenaProgress((id, progress)) => self.arena.progress(id, progress),
+            Message::ArenaVote(index) => {
+                if !self.arena.is_running() && index < self.arena.columns.len() {
+                    self.arena.winner = Some(index);
+                }
+            }
+            Message::ContinueArenaWinner => {
+                let Some(column) = self.arena.winner.and_then(|i| self.arena.columns.get(i)) else {
+                    return;
+                };
+                let mut chat = OllamaChat::new(column.model.clone(), self.arena.server);
+                chat.display_name = tr_args(
+                    "arena-chat-name",
+                    &[("model", column.model.as_str().into())],
+                );
+                chat.chat_entries.push(ChatEntry {
+                    prompt: self.arena.asked.clone(),
+                    response: column.response.clone(),
+                    stats: column.stats.clone(),
+                    model: Some(column.model.clone()),
+                });
+                chat.state = ChatState::Finished;
+                chat.save_chat_history();
+                self.current_chat = chat.uuid;
+                self.chats.push(chat);
+                self.arena = Arena {
+                    selected: std::mem::take(&mut self.arena.selected),
+                    ..Arena::default()
+                };
+                self.state = AppState::Chat;
+            }
+            Message::SummaryFinished(id, result) => {
+                let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) else {
+                    return;
+                };
+                let Some(target) = chat.summarizing.take() else {
+                    return;
+                };
+                let summary = match result {
+                    Ok(summary) => summary,
+                    Err(e) => {
+                        tracing::error!(chat = %id, error = ?e, "summary failed");
+                        self.notice = Some(tr_args(
+                            "chat-summary-failed",
+                            &[("error", e.to_string().into())],
+                        ));
+                        return;
+                    }
+                };
+                match target {
+                    SummaryTarget::TrimHistory => chat.apply_summary(summary),
+                    SummaryTarget::NewChat => {
+                        let mut continuation = OllamaChat::new(chat.model.clone(), chat.server);
+                        continuation.display_name = tr_args(
+                            "chat-continued",
+                            &[("name", chat.display_name.as_str().into())],
+                        );
+                        continuation.num_ctx = chat.num_ctx;
+                        continuation.summary = Some(summary);
+                        continuation.save_chat_history();
+                        self.current_chat = continuation.uuid;
+                        self.chats.push(continuation);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Puts `template` into the chat's input, or opens the variable form first.
+    /// `rest` (text typed after a `/command`) fills the first variable.
+    fn insert_prompt(&mut self, chat: Uuid, template: PromptTemplate, rest: String) {
+        let variables = template.variables();
+        if variables.is_empty() {
+            if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == chat) {
+                chat.set_input(&if rest.is_empty() {
+                    template.body
+                } else {
+                    format!("{}\n\n{}", template.body, rest)
+                });
+            }
+            return;
+        }
+        let mut values: Vec<(String, String)> = variables
+            .into_iter()
+            .map(|name| (name, String::new()))
+            .collect();
+        values[0].1 = rest;
+        self.prompt_form = Some(PromptForm {
+            chat,
+            template,
+            values,
+        });
+    }
+
+    fn create_job(&mut self, id: Uuid) -> Option<&mut DownloadProgress> {
+        self.model_editor
+            .creating
+            .iter_mut()
+            .map(|job| &mut job.progress)
+            .find(|progress| progress.id == id)
+    }
+
+    fn context_length(&self, chat: &OllamaChat) -> Option<u64> {
+        chat.num_ctx.or_else(|| {
+            self.context_lengths
+                .get(&(chat.server, chat.model.clone()))
+                .copied()
+                .flatten()
+        })
+    }
+
+    pub fn theme(&self) -> iced::Theme {
+        if let Some(preview) = self.theme_editor.as_ref().and_then(|e| e.to_theme().ok()) {
+            return preview;
+        }
+        // A theme given on the command line wins over the system's mode.
+        let from_command_line = self
+            .overrides
+            .theme
+            .as_ref()
+            .is_some_and(|(name, _)| *name == theme_key(&self.theme));
+        if !self.follow_system_theme || from_command_line {
+            self.theme.clone()
+        } else if self.system_dark {
+            self.dark_theme.clone()
+        } else {
+            self.light_theme.clone()
+        }
+    }
+
+    pub fn subscription(&self) -> Subscription<Message> {
+        let chat_subs = self.chats.iter().map(|chat| {
+            let server = self.server_by_id(chat.server);
+            chat.subscription(server.backend(), self.client(chat.server), &server.url)
+        });
+
+        let title_subs = self.chats.iter().filter(|chat| chat.titling).map(|chat| {
+            let server = self.server_by_id(chat.server);
+            match self.client(chat.server) {
+                Ok(client) => subscribe_to_completion(
+                    (chat.uuid, "title"),
+                    server.backend(),
+                    client,
+                    &server.url,
+                    self.title_model_for(chat),
+                    chat.title_prompt(),
+                ),
+                Err(e) => subscribe_to_client_error((chat.uuid, "title"), e),
+            }
+            .map(|((id, _), result)| Message::TitleGenerated(id, result))
+        });
+
+        // Poll the active server, and any server a queued prompt is waiting on.
+        let mut watched: Vec<Uuid> = self
+            .chats
+            .iter()
+            .filter(|chat| chat.is_queued())
+            .map(|chat| self.server_by_id(chat.server).id)
+            .chain(std::iter::once(self.active_server))
+            .collect();
+        watched.sort();
+        watched.dedup();
+        let health_subs = watched.into_iter().map(|server| {
+            let profile = self.server_by_id(server);
+            let fingerprint = (profile.backend, profile.connection_fingerprint());
+            match self.client(server) {
+                Ok(client) => subscribe_to_health(
+                    profile.backend(),
+                    client,
+                    &profile.url,
+                    server,
+                    fingerprint,
+                ),
+                Err(e) => subscribe_to_client_error((server, fingerprint), e)
+                    .map(|((server, _), result)| (server, result)),
+            }
+            .map(|(server, result)| Message::HealthChecked(server, result))
+        });
+
+        let connection_test_sub =
+            match (&self.connection_test, normalize_url(&self.server_draft.url)) {
+                (Some(ConnectionTest::Running(id)), Ok(url)) => {
+                    let mut draft = self.server_draft.clone();
+                    draft.url = url;
+                    draft.secrets.headers = parse_headers(&self.server_headers.text());
+                    Some(test_connection(*id, &draft))
+                }
+                _ => None,
+            };
+
+        let arena_subs = self
+            .arena
+            .columns
+            .iter()
+            .filter(|column| matches!(column.state, ChatState::Streaming))
+            .map(|column| {
+                let request = ChatRequest {
+                    model: column.model.clone(),
+                    prompt: self.arena.asked.clone(),
+                    history: Vec::new(),
+                    summary: None,
+                    context: None,
+                    num_ctx: None,
+                };
+                let server = self.server_by_id(self.arena.server);
+                match self.client(server.id) {
+                    Ok(client) => subscribe_to_stream(
+                        column.id,
+                        server.backend(),
+                        client,
+                        &server.url,
+                        request,
+                    ),
+                    Err(e) => subscribe_to_client_error(column.id, e),
+                }
+                .map(Message::ArenaProgress)
+            });
+
+        let mut missing_models: Vec<ModelKey> = self
+            .chats
+            .iter()
+            .map(|chat| (self.server_by_id(chat.server).id, chat.model.clone()))
+            .filter(|key| !self.context_lengths.contains_key(key))
+            .collect();
+        missing_models.sort();
+        missing_models.dedup();
+        // The lookup is only a hint, so it waits until the server's settings are fixed.
+        let context_length_subs = missing_models.into_iter().filter_map(|(server, model)| {
+            let profile = self.server_by_id(server);
+            let attempt = self
+                .context_length_attempts
+                .get(&(server, model.clone()))
+                .copied()
+                .unwrap_or(0);
+            let client = self.client(server).ok()?;
+            Some(
+                subscribe_to_context_length(
+                    profile.backend(),
+                    client,
+                    &profile.url,
+                    server,
+                    model,
+                    attempt,
+                )
+                .map(|(key, result)| Message::ContextLengthLoaded(key, result)),
+            )
+        });
+
+        let download_subs = self
+            .downloads
+            .iter()
+            .filter(|dl| dl.state == DownloadState::Active)
+            .map(|dl| match self.client(dl.server) {
+                Ok(client) => subscribe_to_download(
+                    dl.progress.id,
+                    dl.attempts,
+                    client,
+                    dl.url.clone(),
+                    dl.progress.model.clone(),
+                )
+                .map(|(id, result)| Message::DownloadProgress(id, result)),
+                Err(e) => subscribe_to_client_error((dl.progress.id, dl.attempts), e)
+                    .map(|((id, _), result)| Message::DownloadProgress(id, result)),
+            });
+
+        let model_call_subs = self.model_calls.iter().map(|pending| {
+            let server = self.server_by_id(pending.server);
+            match self.client(pending.server) {
+                Ok(client) => {
+                    subscribe_to_model_call(pending.id, client, server, pending.call.clone())
+                }
+                Err(e) => subscribe_to_client_error(pending.id, e)
+                    .map(|(id, result)| Message::ModelCallFinished(id, result)),
+            }
+        });
+
+        let create_subs = self.model_editor.creating.iter().map(|job| {
+            match self.client(job.server) {
+                Ok(client) => subscribe_to_status_stream(
+                    job.progress.id,
+                    client,
+                    self.server_by_id(job.server).endpoint("/api/create"),
+                    job.body.clone(),
+                ),
+                Err(e) => subscribe_to_client_error(job.progress.id, e),
+            }
+            .map(|(id, result)| Message::CreateProgress(id, result))
+        });
+
+        // Drafts are written a moment after typing stops instead of on every key.
+        let draft_sub = self
+            .chats
+            .iter()
+            .any(|chat| chat.draft_dirty)
+            .then(|| iced::time::every(Duration::from_secs(2)).map(|_| Message::SaveDrafts));
+
+        let event_sub = event::listen_with(|event, status, _window| match event {
+            iced::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => Some(
+                Message::KeyPressed(key, modifiers, status == event::Status::Captured),
+            ),
+            iced::Event::Window(window::Event::Resized(size)) => Some(Message::WindowResized(size)),
+            iced::Event::Window(window::Event::Moved(position)) => {
+                Some(Message::WindowMoved(position))
+            }
+            _ => None,
+        });
+        let close_sub = window::close_requests().map(Message::WindowCloseRequested);
+        let system_theme_sub = self
+            .follow_system_theme
+            .then(|| iced::time::every(Duration::from_secs(5)).map(|_| Message::CheckSystemTheme));
+        let drag_sub = self.dragging_sidebar.then(|| {
+            event::listen_with(|event, _status, _window| match event {
+                iced::Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                    Some(Message::SidebarDragged(position.x))
+                }
+                iced::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                    Some(Message::SidebarDragEnded)
+                }
+                _ => None,
+            })
+        });
+
+        let api_sub = self
+            .api_enabled
+            .then_some(self.api_token.clone())
+            .flatten()
+            .map(|token| {
+                Subscription::run_with_id(
+                    ("automation", self.api_port),
+                    automation::serve(self.api_port, token),
+                )
+                .map(Message::Api)
+            });
+
+        Subscription::batch(
+            [event_sub, close_sub]
+                .into_iter()
+                .chain(drag_sub)
+                .chain(system_theme_sub)
+                .chain(api_sub)
+                .chain(chat_subs)
+                .chain(draft_sub)
+                .chain(download_subs)
+                .chain(context_length_subs)
+                .chain(create_subs)
+                .chain(model_call_subs)
+                .chain(health_subs)
+                .chain(connection_test_sub)
+                .chain(arena_subs)
+                .chain(title_subs),
+        )
+    }
+
+    pub fn view(&self) -> Element<Message> {
+        let compact = self.compact();
+        let nav_padding = if compact { [2, 6] } else { [5, 10] };
+        let manages_models = self.manages_models();
+        let nav_button = |label: String, state: AppState| {
+            let available = manages_models || !state.needs_model_management();
+            button(text(label))
+                .on_press_maybe(available.then_some(Message::ChangeAppState(state)))
+                .padding(nav_padding)
+                .width(Length::Shrink)
+        };
+        let top_nav = row![
+            nav_button(tr("nav-chats"), AppState::Chat),
+            nav_button(tr("nav-settings"), AppState::Settings),
+            nav_button(tr("nav-models"), AppState::Models),
+            nav_button(tr("nav-library"), AppState::Library),
+            nav_button(tr("nav-prompts"), AppState::Prompts),
+            nav_button(tr("nav-arena"), AppState::Arena),
+            nav_button(tr("nav-statistics"), AppState::Statistics),
+            nav_button(tr("nav-diagnostics"), AppState::Diagnostics),
+            iced::widget::pick_list(
+                self.servers.clone(),
+                Some(self.server().clone()),
+                Message::SelectServer
+            )
+            .padding(nav_padding),
+            self.health_badge(),
+        ]
+        .push_maybe(self.notice.as_ref().map(|notice| {
+            row![text(notice), button("✖").on_press(Message::DismissNotice)]
+                .spacing(5)
+                .align_y(Alignment::Center)
+        }))
+        .spacing(if compact { 3 } else { 5 })
+        .padding([0, 5])
+        .align_y(Alignment::Start);
+
+        let page: Element<Message> = match self.state {
+            AppState::Chat => {
+                let sidebar_chats = scrollable(
+                    column(
+                        self.chats
+                            .iter()
+                            .filter(|chat| {
+                                fuzzy_score(&self.chat_search, &chat.display_name).is_some()
+                            })
+                            .map(|chat| {
+                                chat.sidebar_view(
+                                    chat.uuid == self.current_chat,
+                                    self.editing_chat == Some(chat.uuid),
+                                )
+                            }),
+                    )
+                    .spacing(5),
+                )
+                .spacing(5)
+                .width(Length::Fill)
+                .height(Length::Fill);
+
+                let left_sidebar: Element<Message> = if self.sidebar_collapsed {
+                    column![button("»")
+                        .on_press(Message::ToggleSidebar)
+                        .padding(nav_padding)]
+                    .padding([5, 0])
+                    .into()
+                } else {
+                    let sidebar = column![
+                        row![
+                            button(text(tr("sidebar-new-chat")))
+                                .on_press(Message::NewChat)
+                                .padding(nav_padding),
+                            iced::widget::horizontal_space(),
+                            button("«")
+                                .on_press(Message::ToggleSidebar)
+                                .padding(nav_padding),
+                        ]
+                        .padding([5, 0]),
+                        text_input(&tr("sidebar-search"), &self.chat_search)
+                            .id(text_input::Id::new(CHAT_SEARCH))
+                            .on_input(Message::ChatSearchChanged)
+                            .padding(if compact { 3 } else { 5 }),
+                        sidebar_chats
+                    ]
+                    .spacing(if compact { 3 } else { 5 })
+                    .width(Length::Fixed(self.sidebar_width()));
+                    let splitter = mouse_area(
+                        container(vertical_rule(1))
+                            .padding([0, 4])
+                            .height(Length::Fill),
+                    )
+                    .on_press(Message::SidebarDragStarted)
+                    .interaction(mouse::Interaction::ResizingHorizontally);
+                    row![sidebar, splitter].into()
+                };
+
+                let current_chat = self
+                    .chats
+                    .iter()
+                    .find(|c| c.uuid == self.current_chat)
+                    .map(|chat| {
+                        chat.main_view(
+                            self.context_length(chat),
+                            self.health(chat.server).is_offline(),
+                            self.local_models
+                                .get(&chat.server)
+                                .map(|models| models.iter().map(|m| m.name.clone()).collect())
+                                .unwrap_or_default(),
+                            &self.prompts.templates,
+                            self.send_key,
+                            self.fonts.monospace_font(),
+                        )
+                    })
+                    .unwrap_or_else(|| column!().into());
+
+                let current_chat = column![current_chat]
+                    .push_maybe(
+                        self.prompt_form
+                            .as_ref()
+                            .filter(|form| form.chat == self.current_chat)
+                            .map(prompt_form_view),
+                    )
+                    .height(Length::Fill);
+
+                let main_content = container(current_chat)
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .align_x(Horizontal::Center);
+
+                column![
+                    top_nav,
+                    row![left_sidebar, main_content]
+                        .spacing(if compact { 2 } else { 5 })
+                        .padding(5)
+                        .height(Length::Fill)
+                ]
+                .into()
+            }
+            AppState::Settings => {
+                let downloads_view = if self.downloads.is_empty() {
+                    column!().into()
+                } else {
+                    column(
+                        self.downloads
+                            .iter()
+                            .map(DownloadJob::view)
+                            .collect::<Vec<_>>(),
+                    )
+                    .spacing(10)
+                };
+
+                let can_download = manages_models && !self.download_model_input.is_empty();
+                column![
+                    top_nav,
+                    column![
+                        self.appearance_view(),
+                        row![
+                            text(tr("settings-language")),
+                            iced::widget::pick_list(
+                                Language::ALL,
+                                Some(self.language),
+                                Message::ChangeLanguage
+                            )
+                            .padding([5, 10]),
+                        ]
+                        .spacing(10)
+                        .align_y(Alignment::Center),
+                        text(tr("settings-servers")).size(16),
+                        self.servers_view(),
+                        text(tr("settings-select-model")).size(16),
+                        iced::widget::pick_list(
+                            self.model_names(),
+                            Some(self.selected_model.clone()),
+                            Message::ChangeSelectedModel
+                        )
+                        .padding([5, 10])
+                        .width(Length::Shrink),
+                        button(text(tr("settings-refresh-models")))
+                            .on_press(Message::RefreshModels),
+                        row![
+                            text(tr("settings-send-with")),
+                            iced::widget::pick_list(
+                                SendKey::ALL,
+                                Some(self.send_key),
+                                Message::ChangeSendKey
+                            )
+                            .padding([5, 10]),
+                        ]
+                        .spacing(10)
+                        .align_y(Alignment::Center),
+                        self.shortcuts_view(),
+                        self.api_view(),
+                        checkbox(tr("settings-auto-titles"), self.auto_titles)
+                            .on_toggle(Message::ToggleAutoTitles),
+                        row![
+                            text(tr("settings-title-model")),
+                            iced::widget::pick_list(
+                                std::iter::once(tr("settings-same-as-chat"))
+                                    .chain(self.model_names())
+                                    .collect::<Vec<_>>(),
+                                Some(
+                                    self.title_model
+                                        .clone()
+                                        .unwrap_or_else(|| tr("settings-same-as-chat"))
+                                ),
+                                Message::ChangeTitleModel
+                            )
+                            .padding([5, 10]),
+                        ]
+                        .spacing(10)
+                        .align_y(Alignment::Center),
+                        row![
+                            text(tr("settings-download-model")).size(16),
+                            button(text(tr("settings-browse-library")))
+                                .on_press_maybe(
+                                    manages_models
+                                        .then_some(Message::ChangeAppState(AppState::Library))
+                                )
+                                .padding([5, 10]),
+                        ]
+                        .spacing(10)
+                        .align_y(Alignment::Center),
+                        row![
+                            text_input(&tr("settings-model-name"), &self.download_model_input)
+                                .on_input(Message::DownloadModelInputChanged)
+                                .padding(5)
+                                .width(Length::Fixed(300.0))
+                                .on_submit_maybe(if !can_download {
+                                    None
+                                } else {
+                                    Some(Message::StartDownloadModel)
+                                }),
+                            button(text(tr("settings-download")))
+                                .on_press_maybe(if !can_download {
+                                    None
+                                } else {
+                                    Some(Message::StartDownloadModel)
+                                })
+                                .padding([5, 10])
+                        ],
+                        row![
+                            text(tr("settings-parallel-downloads")),
+                            iced::widget::pick_list(
+                                [1, 2, 3, 4],
+                                Some(self.max_concurrent_downloads),
+                                Message::ChangeMaxDownloads
+                            )
+                            .padding([5, 10]),
+                        ]
+                        .spacing(10)
+                        .align_y(Alignment::Center),
+                        downloads_view
+                    ]
+                    .spacing(10)
+                    .padding(10)
+                    .align_x(Alignment::Start)
+                ]
+                .into()
+            }
+            AppState::Statistics => column![top_nav, self.statistics_view()].into(),
+            AppState::Models => column![top_nav, self.models_view()].into(),
+            AppState::ModelEditor => column![top_nav, self.model_editor_view()].into(),
+            AppState::Library => column![top_nav, self.library_view()].into(),
+            AppState::Arena => column![top_nav, self.arena_view()].into(),
+            AppState::Prompts => column![top_nav, self.prompts_view()].into(),
+            AppState::Diagnostics => column![top_nav, self.diagnostics_view()].into(),
+        };
+
+        match &self.palette {
+            Some(palette) => iced::widget::stack![page, self.palette_view(palette)].into(),
+            None => page,
+        }
+    }
+
+    fn palette_view(&self, palette: &CommandPalette) -> Element<'_, Message> {
+        let entries = self.palette_entries(&palette.query);
+        let list = column(entries.iter().enumerate().map(|(index, entry)| {
+            let keys = match entry {
+                PaletteEntry::Action(action) => self.keymap.keys(*action),
+                _ => None,
+            };
+            button(
+                row![text(entry.to_string()).width(Length::Fill)]
+                    .push_maybe(keys.map(|keys| text(keys).size(12)))
+                    .align_y(Alignment::Center),
+            )
+            .on_press(Message::PaletteSelect(index))
+            .style(if index == palette.selected {
+                button::primary
+            } else {
+                button::text
+            })
+            .padding([5, 10])
+            .width(Length::Fill)
+            .into()
+        }))
+        .spacing(2);
+
+        let panel = column![text_input(&tr("palette-placeholder"), &palette.query)
+            .id(text_input::Id::new(PALETTE_INPUT))
+            .on_input(Message::PaletteQueryChanged)
+            .on_submit(Message::PaletteSelect(palette.selected))
+            .padding(10),]
+        .push(if entries.is_empty() {
+            Element::from(text(tr("palette-no-matches")).size(14))
+        } else {
+            list.into()
+        })
+        .push(
+            row![
+                text(tr("palette-hint")).size(12).width(Length::Fill),
+                button(text(tr("palette-close")))
+                    .on_press(Message::ClosePalette)
+                    .style(button::text),
+            ]
+            .align_y(Alignment::Center),
+        )
+        .spacing(10);
+
+        container(
+            container(panel)
+                .width(Length::Fixed(520.0))
+                .padding(10)
+                .style(container::bordered_box),
+        )
+        .width(Length::Fill)
+        .align_x(Horizontal::Center)
+        .padding([60, 0])
+        .into()
+    }
+
+    fn shortcuts_view(&self) -> Element<'_, Message> {
+        column![text(tr("shortcuts-title")).size(16)]
+            .extend(Action::ALL.into_iter().map(|action| {
+                row![
+                    text(action.to_string()).width(Length::Fixed(220.0)),
+                    text(self.keymap.keys(action).unwrap_or_else(|| "—".to_string())),
+                ]
+                .into()
+            }))
+            .push(text(tr("shortcuts-hint")).size(12))
+            .spacing(5)
+            .into()
+    }
+
+    fn appearance_view(&self) -> Element<'_, Message> {
+        let themes = if self.follow_system_theme {
+            row![
+                text(tr("appearance-light")),
+                iced::widget::pick_list(
+                    self.theme_choices(),
+                    Some(self.light_theme.clone()),
+                    Message::ChangeLightTheme
+                )
+                .padding([5, 10]),
+                text(tr("appearance-dark")),
+                iced::widget::pick_list(
+                    self.theme_choices(),
+                    Some(self.dark_theme.clone()),
+                    Message::ChangeDarkTheme
+                )
+                .padding([5, 10]),
+            ]
+        } else {
+            row![iced::widget::pick_list(
+                self.theme_choices(),
+                Some(self.theme.clone()),
+                Message::ChangeTheme
+            )
+            .padding([5, 10])]
+        };
+        let themes =
+            themes
+                .push(
+                    checkbox(tr("appearance-follow-system"), self.follow_system_theme)
+                        .on_toggle(Message::FollowSystemTheme),
+                )
+                .push_maybe(self.theme_editor.is_none().then(|| {
+                    button(text(tr("appearance-edit-theme"))).on_press(Message::EditTheme)
+                }))
+                .push(button(text(tr("appearance-reload-themes"))).on_press(Message::ReloadThemes))
+                .spacing(10)
+                .align_y(Alignment::Center);
+
+        let fonts = row![
+            text(tr("appearance-font")),
+            text_input(&tr("appearance-font-default"), &self.font_form.ui)
+                .on_input(Message::ChangeUiFont)
+                .on_submit(Message::ApplyFonts)
+                .padding(5)
+                .width(Length::Fixed(160.0)),
+            text(tr("appearance-code-font")),
+            text_input(
+                &tr("appearance-code-font-default"),
+                &self.font_form.monospace
+            )
+            .on_input(Message::ChangeMonospaceFont)
+            .on_submit(Message::ApplyFonts)
+            .padding(5)
+            .width(Length::Fixed(160.0)),
+            text(tr("appearance-size")),
+            text_input("16", &self.font_form.size)
+                .on_input(Message::ChangeTextSize)
+                .on_submit(Message::ApplyFonts)
+                .padding(5)
+                .width(Length::Fixed(60.0)),
+            button(text(tr("appearance-apply"))).on_press(Message::ApplyFonts),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center);
+
+        let zoom = row![
+            text(tr("appearance-zoom")),
+            button("-").on_press(Message::Zoom(self.zoom - ZOOM_STEP)),
+            text(format!("{:.0}%", self.zoom * 100.0)),
+            button("+").on_press(Message::Zoom(self.zoom + ZOOM_STEP)),
+            button(text(tr("appearance-reset"))).on_press(Message::Zoom(default_zoom())),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center);
+
+        column![text(tr("appearance-title")).size(16), themes]
+            .push_maybe(self.theme_editor.as_ref().map(theme_editor_view))
+            .push(fonts)
+            .push(
+                text(tr_args(
+                    "appearance-fonts-hint",
+                    &[("dir", appearance::THEMES_DIR.into())],
+                ))
+                .size(12),
+            )
+            .push(zoom)
+            .spacing(5)
+            .into()
+    }
+
+    fn api_view(&self) -> Element<'_, Message> {
+        let status = match (&self.api_token, &self.api_error) {
+            (_, Some(e)) => Some(text(e.clone()).style(text::danger)),
+            (None, _) if self.api_enabled => {
+                Some(text(tr("api-token-missing")).style(text::danger))
+            }
+            _ if self.api_enabled => Some(text(tr_args(
+                "api-listening",
+                &[("port", self.api_port.into())],
+            ))),
+            _ => None,
+        };
+        column![
+            text(tr("api-title")).size(16),
+            row![
+                checkbox(tr("api-enable"), self.api_enabled).on_toggle(Message::ToggleApi),
+                text(tr("api-port")),
+                text_input("11435", &self.api_port_input)
+                    .on_input(Message::ChangeApiPort)
+                    .on_submit(Message::ApplyApiPort)
+                    .padding(5)
+                    .width(Length::Fixed(80.0)),
+                button(text(tr("api-apply")))
+                    .on_press_maybe(
+                        (self.api_port_input != self.api_port.to_string())
+                            .then_some(Message::ApplyApiPort)
+                    )
+                    .padding([5, 10]),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+        ]
+        .push_maybe(status.map(|status| status.size(12)))
+        .spacing(5)
+        .into()
+    }
+
+    fn health_badge(&self) -> Element<'_, Message> {
+        let health = self.health(self.active_server);
+        let badge = text(health.label());
+        let badge = match &health {
+            ServerHealth::Unknown => badge,
+            ServerHealth::Connected { .. } => badge.style(text::success),
+            ServerHealth::Disconnected { .. } => badge.style(text::danger),
+        };
+        let details = match &health {
+            ServerHealth::Disconnected { error, failures } => tr_args(
+                "health-failed-checks",
+                &[
+                    ("failures", (*failures).into()),
+                    ("error", error.as_str().into()),
+                ],
+            ),
+            _ => self.server().url.clone(),
+        };
+        iced::widget::tooltip(
+            container(badge).padding([5, 0]),
+            container(text(details).size(12))
+                .padding(5)
+                .style(container::rounded_box),
+            iced::widget::tooltip::Position::Bottom,
+        )
+        .into()
+    }
+
+    fn servers_view(&self) -> Element<'_, Message> {
+        let draft = &self.server_draft;
+        let profiles = column(self.servers.iter().map(|server| {
+            let label = if server.id == self.active_server {
+                tr_args("server-active", &[("name", server.name.as_str().into())])
+            } else {
+                server.name.clone()
+            };
+            row![
+                text(label).width(Length::Fixed(200.0)),
+                text(&server.url).width(Length::Fixed(250.0)),
+                button(text(tr("server-edit")))
+                    .on_press(Message::EditServer(server.clone()))
+                    .padding([5, 10]),
+                button(text(tr("server-delete")))
+                    .on_press_maybe(
+                        (self.servers.len() > 1).then_some(Message::DeleteServer(server.id))
+                    )
+                    .padding([5, 10]),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .into()
+        }))
+        .spacing(5);
+
+        let url_check = normalize_url(&draft.url);
+        let url_status = match (&url_check, &self.connection_test) {
+            (Err(e), _) => Some(text(e.clone()).style(text::danger)),
+            (Ok(_), Some(ConnectionTest::Running(_))) => Some(text(tr("server-testing"))),
+            (Ok(_), Some(ConnectionTest::Succeeded(version))) => Some(
+                text(tr_args(
+                    "server-connected",
+                    &[("version", version.as_str().into())],
+                ))
+                .style(text::success),
+            ),
+            (Ok(_), Some(ConnectionTest::Failed(e))) => Some(
+                text(tr_args(
+                    "server-connection-failed",
+                    &[("error", e.as_str().into())],
+                ))
+                .style(text::danger),
+            ),
+            (Ok(_), None) => None,
+        };
+
+        let editor = column![
+            column![row![
+                text_input(&tr("server-name"), &draft.name)
+                    .on_input(Message::ServerNameChanged)
+                    .padding(5)
+                    .width(Length::Fixed(200.0)),
+                text_input(&tr("server-url-placeholder"), &draft.url)
+                    .on_input(Message::ChangeDefaultUrl)
+                    .padding(5)
+                    .width(Length::Fixed(300.0)),
+                button(text(tr("server-test")))
+                    .on_press_maybe(
+                        (url_check.is_ok()
+                            && !matches!(self.connection_test, Some(ConnectionTest::Running(_))))
+                        .then_some(Message::TestConnection)
+                    )
+                    .padding([5, 10]),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center)]
+            .push_maybe(url_status.map(|status| status.size(12)))
+            .spacing(5),
+            row![
+                text(tr("server-protocol")),
+                iced::widget::pick_list(
+                    BackendKind::ALL,
+                    Some(draft.backend),
+                    Message::ServerBackendChanged
+                )
+                .padding([5, 10]),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+            row![iced::widget::pick_list(
+                AuthKind::ALL,
+                Some(draft.auth),
+                Message::ServerAuthKindChanged
+            )
+            .padding([5, 10]),]
+            .push_maybe(match draft.auth {
+                AuthKind::None => None,
+                AuthKind::Bearer =>
+                    Some(row![text_input(&tr("server-token"), &draft.secrets.token)
+                        .on_input(Message::ServerTokenChanged)
+                        .secure(true)
+                        .padding(5)
+                        .width(Length::Fixed(300.0))]),
+                AuthKind::Basic => Some(
+                    row![
+                        text_input(&tr("server-username"), &draft.username)
+                            .on_input(Message::ServerUsernameChanged)
+                            .padding(5)
+                            .width(Length::Fixed(200.0)),
+                        text_input(&tr("server-password"), &draft.secrets.password)
+                            .on_input(Message::ServerPasswordChanged)
+                            .secure(true)
+                            .padding(5)
+                            .width(Length::Fixed(200.0)),
+                    ]
+                    .spacing(10),
+                ),
+            })
+            .spacing(10)
+            .align_y(Alignment::Center),
+            text(tr("server-headers")).size(12),
+            text_editor(&self.server_headers)
+                .on_action(Message::ServerHeadersAction)
+                .height(Length::Fixed(60.0))
+                .width(510),
+            row![
+                text_input(
+                    &tr("server-ca-cert"),
+                    &draft
+                        .ca_cert
+                        .as_ref()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_default()
+                )
+                .on_input(Message::ServerCaCertChanged)
+                .padding(5)
+                .width(Length::Fixed(250.0)),
+                text_input(
+                    &tr("server-client-cert"),
+                    &draft
+                        .client_identity
+                        .as_ref()
+                        .map(|p| p.display().to_string())
+                        .unwrap_or_default()
+                )
+                .on_input(Message::ServerIdentityChanged)
+                .padding(5)
+                .width(Length::Fixed(250.0)),
+            ]
+            .spacing(10),
+            row![
+                text_input(
+                    &tr("server-timeout"),
+                    &draft
+                        .timeout_secs
+                        .map(|t| t.to_string())
+                        .unwrap_or_default()
+                )
+                .on_input(Message::ServerTimeoutChanged)
+                .padding(5)
+                .width(Length::Fixed(100.0)),
+                checkbox(
+                    tr("server-accept-invalid-certs"),
+                    draft.accept_invalid_certs
+                )
+                .on_toggle(Message::ServerInsecureToggled),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+            text(tr("server-secrets-hint")).size(12),
+            row![
+                button(text(tr("server-save")))
+                    .on_press_maybe(
+                        (!draft.name.trim().is_empty() && url_check.is_ok())
+                            .then_some(Message::SaveServer)
+                    )
+                    .padding([5, 10]),
+                button(text(tr("server-new")))
+                    .on_press(Message::NewServer)
+                    .padding([5, 10]),
+            ]
+            .spacing(10),
+        ]
+        .spacing(5);
+
+        column![profiles, editor].spacing(10).into()
+    }
+
+    fn models_view(&self) -> Element<'_, Message> {
+        let manager = &self.model_manager;
+        let filter = manager.filter.to_lowercase();
+        let mut models: Vec<&ModelInfo> = self
+            .model_infos()
+            .iter()
+            .filter(|m| {
+                filter.is_empty()
+                    || m.name.to_lowercase().contains(&filter)
+                    || m.details.family.to_lowercase().contains(&filter)
+            })
+            .collect();
+        match manager.sort {
+            ModelSort::Name => models.sort_by(|a, b| a.name.cmp(&b.name)),
+            ModelSort::Size => models.sort_by_key(|m| std::cmp::Reverse(m.size)),
+            ModelSort::Modified => models.sort_by(|a, b| b.modified_at.cmp(&a.modified_at)),
+            ModelSort::Family => models
+                .sort_by(|a, b| (&a.details.family, &a.name).cmp(&(&b.details.family, &b.name))),
+        }
+
+        let controls = row![
+            text_input(&tr("models-filter"), &manager.filter)
+                .on_input(Message::ModelFilterChanged)
+                .padding(5)
+                .width(Length::Fixed(300.0)),
+            text(tr("models-sort-by")),
+            iced::widget::pick_list(
+                ModelSort::ALL,
+                Some(manager.sort),
+                Message::ModelSortChanged
+            )
+            .padding([5, 10]),
+            button(text(tr("settings-refresh-models"))).on_press(Message::RefreshModels),
+            button(text(tr("models-new"))).on_press(Message::NewCustomModel),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center);
+
+        let header = row![
+            text(tr("models-name"))
+                .size(16)
+                .width(Length::FillPortion(3)),
+            text(tr("models-size"))
+                .size(16)
+                .width(Length::FillPortion(1)),
+            text(tr("models-family"))
+                .size(16)
+                .width(Length::FillPortion(1)),
+            text(tr("models-parameters"))
+                .size(16)
+                .width(Length::FillPortion(1)),
+            text(tr("models-quantization"))
+                .size(16)
+                .width(Length::FillPortion(1)),
+            text(tr("models-digest"))
+                .size(16)
+                .width(Length::FillPortion(1)),
+            text(tr("models-modified"))
+                .size(16)
+                .width(Length::FillPortion(2)),
+            text("").width(Length::Fixed(150.0)),
+        ]
+        .spacing(10);
+
+        let rows = column(models.into_iter().map(|m| {
+            let delete = if manager.confirm_delete.as_ref() == Some(&m.name) {
+                button(text(tr("models-confirm")))
+                    .on_press(Message::ConfirmDeleteModel(m.name.clone()))
+            } else {
+                button(text(tr("models-delete"))).on_press(Message::DeleteModel(m.name.clone()))
+            };
+            row![
+                text(&m.name).width(Length::FillPortion(3)),
+                text(format_size(m.size)).width(Length::FillPortion(1)),
+                text(&m.details.family).width(Length::FillPortion(1)),
+                text(&m.details.parameter_size).width(Length::FillPortion(1)),
+                text(&m.details.quantization_level).width(Length::FillPortion(1)),
+                text(m.digest.chars().take(12).collect::<String>()).width(Length::FillPortion(1)),
+                text(
+                    m.modified_at
+                        .chars()
+                        .take(16)
+                        .collect::<String>()
+                        .replace('T', " ")
+                )
+                .width(Length::FillPortion(2)),
+                row![
+                    button(text(tr("models-details")))
+                        .on_press(Message::SelectManagedModel(m.name.clone())),
+                    delete
+                ]
+                .spacing(5)
+                .width(Length::Fixed(150.0)),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .into()
+        }))
+        .spacing(5);
+
+        let running = column![
+            row![
+                text(tr("models-loaded")).size(24),
+                button(text(tr("models-refresh"))).on_press(Message::RefreshRunningModels)
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+            column(manager.running.iter().map(|m| {
+                row![
+                    text(&m.name).width(Length::FillPortion(3)),
+                    text(tr_args(
+                        "models-vram",
+                        &[
+                            ("vram", format_size(m.size_vram).into()),
+                            ("size", format_size(m.size).into()),
+                        ],
+                    ))
+                    .width(Length::FillPortion(2)),
+                    text(tr_args(
+                        "models-until",
+                        &[(
+                            "time",
+                            m.expires_at
+                                .chars()
+                                .take(19)
+                                .collect::<String>()
+                                .replace('T', " ")
+                                .into(),
+                        )],
+                    ))
+                    .width(Length::FillPortion(2)),
+                    button(text(tr("models-unload")))
+                        .on_press(Message::UnloadModel(m.name.clone())),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center)
+                .into()
+            }))
+            .spacing(5),
+        ]
+        .spacing(10);
+
+        let details = match (&manager.selected, &manager.show) {
+            (Some(name), Some(show)) => {
+                let section = |title: &str, body: &str| -> Element<'_, Message> {
+                    column![
+                        text(title.to_string()).size(16),
+                        text(if body.is_empty() { "-" } else { body }.to_string()).size(12)
+                    ]
+                    .spacing(5)
+                    .into()
+                };
+                column![
+                    row![
+                        text(name).size(24),
+                        button(text(tr("models-edit"))).on_press(Message::EditModel(name.clone())),
+                    ]
+                    .spacing(10)
+                    .align_y(Alignment::Center),
+                    row![
+                        text_input(&tr("models-new-name"), &manager.copy_target)
+                            .on_input(Message::CopyTargetChanged)
+                            .padding(5)
+                            .width(Length::Fixed(300.0)),
+                        button(text(tr("models-copy"))).on_press(Message::CopyModel),
+                        button(text(tr("models-rename"))).on_press(Message::RenameModel),
+                    ]
+                    .spacing(10),
+                    section(&tr("models-modelfile"), &show.modelfile),
+                    section(&tr("models-parameters"), &show.parameters),
+                    section(&tr("models-template"), &show.template),
+                    section(&tr("models-license"), &show.license),
+                ]
+                .spacing(10)
+            }
+            _ => column![],
+        };
+
+        scrollable(
+            column![controls, header, rows, running, details]
+                .push_maybe(manager.error.as_ref().map(text))
+                .spacing(10)
+                .padding(10),
+        )
+        .height(Length::Fill)
+        .into()
+    }
+
+    fn prompts_view(&self) -> Element<'_, Message> {
+        let editor = &self.prompt_editor;
+        let templates = column(self.prompts.templates.iter().map(|template| {
+            let command = if template.command.is_empty() {
+                String::new()
+            } else {
+                format!("/{}", template.command)
+            };
+            row![
+                text(&template.name).width(Length::Fill),
+                text(command).width(Length::Fixed(120.0)),
+                button(text(tr("prompts-edit")))
+                    .on_press(Message::EditPromptTemplate(template.clone()))
+                    .padding([5, 10]),
+                button(text(tr("prompts-delete")))
+                    .on_press(Message::DeletePromptTemplate(template.name.clone()))
+                    .padding([5, 10]),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .into()
+        }))
+        .spacing(5);
+
+        let can_save = !editor.name.trim().is_empty() && !editor.body.text().trim().is_empty();
+        let form = column![
+            row![
+                text_input(&tr("prompts-name"), &editor.name)
+                    .on_input(Message::PromptTemplateNameChanged)
+                    .padding(5)
+                    .width(Length::Fill),
+                text_input(&tr("prompts-command"), &editor.command)
+                    .on_input(Message::PromptTemplateCommandChanged)
+                    .padding(5)
+                    .width(Length::Fixed(150.0)),
+            ]
+            .spacing(10),
+            text(tr("prompts-variables-hint")).size(12),
+            text_editor(&editor.body)
+                .on_action(Message::PromptTemplateBodyAction)
+                .height(Length::Fixed(150.0)),
+            row![
+                button(text(tr("prompts-save")))
+                    .on_press_maybe(can_save.then_some(Message::SavePromptTemplate))
+                    .padding([5, 10]),
+                button(text(tr("prompts-new")))
+                    .on_press(Message::NewPromptTemplate)
+                    .padding([5, 10]),
+            ]
+            .spacing(10),
+        ]
+        .spacing(8);
+
+        let has_path = !editor.path.trim().is_empty();
+        let sharing = row![
+            text_input(&tr("prompts-path"), &editor.path)
+                .on_input(Message::PromptLibraryPathChanged)
+                .padding(5)
+                .width(Length::Fill),
+            button(text(tr("prompts-import")))
+                .on_press_maybe(has_path.then_some(Message::ImportPrompts))
+                .padding([5, 10]),
+            button(text(tr("prompts-export")))
+                .on_press_maybe(has_path.then_some(Message::ExportPrompts))
+                .padding([5, 10]),
+        ]
+        .spacing(10);
+
+        column![
+            text(tr("prompts-title")).size(24),
+            scrollable(templates).height(Length::FillPortion(1)),
+            form,
+            sharing,
+        ]
+        .push_maybe(editor.status.as_ref().map(|status| text(status).size(12)))
+        .spacing(10)
+        .padding(20)
+        .into()
+    }
+
+    fn arena_view(&self) -> Element<'_, Message> {
+        let arena = &self.arena;
+        let models = row(self.model_infos().iter().map(|model| {
+            let selected = arena.selected.contains(&model.name);
+            let toggle = checkbox(&model.name, selected);
+            if selected || arena.selected.len() < MAX_ARENA_MODELS {
+                let name = model.name.clone();
+                toggle
+                    .on_toggle(move |on| Message::ArenaToggleModel(name.clone(), on))
+                    .into()
+            } else {
+                toggle.into()
+            }
+        }))
+        .spacing(15)
+        .wrap();
+
+        let on_submit = arena.can_start().then_some(Message::StartArena);
+        let prompt_row = row![
+            text_input(&tr("arena-placeholder"), &arena.prompt)
+                .on_input(Message::ArenaPromptChanged)
+                .on_submit_maybe(on_submit.clone())
+                .padding(10)
+                .width(Length::Fill),
+            button(text(tr("arena-compare")))
+                .on_press_maybe(on_submit)
+                .padding([10, 15]),
+        ]
+        .spacing(10);
+
+        let columns = row(arena.columns.iter().enumerate().map(|(index, column)| {
+            let status = match (&column.state, &column.stats) {
+                (ChatState::Streaming, _) => text(tr("arena-generating")).size(12),
+                (ChatState::Errored, _) => text(column.error.clone().unwrap_or_default())
+                    .size(12)
+                    .style(text::danger),
+                (_, Some(stats)) => text(tr_args(
+                    "arena-stats",
+                    &[
+                        ("speed", format!("{:.1}", stats.tokens_per_second()).into()),
+                        ("tokens", stats.eval_count.into()),
+                        ("chars", column.response.chars().count().into()),
+                        ("first", format_duration(stats.time_to_first_token()).into()),
+                    ],
+                ))
+                .size(12),
+                (_, None) => text(""),
+            };
+            let is_winner = arena.winner == Some(index);
+            let vote = button(text(if is_winner {
+                tr("arena-winner")
+            } else {
+                tr("arena-vote")
+            }))
+            .on_press_maybe(
+                (!arena.is_running() && matches!(column.state, ChatState::Finished))
+                    .then_some(Message::ArenaVote(index)),
+            )
+            .padding([5, 10]);
+
+            container(
+                column![
+                    text(&column.model).size(18),
+                    scrollable(text(&column.response).width(Length::Fill)).height(Length::Fill),
+                    status,
+                    vote,
+                ]
+                .spacing(10),
+            )
+            .padding(10)
+            .width(Length::FillPortion(1))
+            .height(Length::Fill)
+            .style(if is_winner {
+                container::bordered_box
+            } else {
+                container::rounded_box
+            })
+            .into()
+        }))
+        .spacing(10)
+        .height(Length::Fill);
+
+        let hint = match arena.selected.len() {
+            n if n < 2 => tr_args("arena-select", &[("max", MAX_ARENA_MODELS.into())]),
+            n => tr_args("arena-selected", &[("count", n.into())]),
+        };
+
+        column![
+            text(tr("nav-arena")).size(24),
+            models,
+            text(hint).size(12),
+            prompt_row,
+        ]
+        .push_maybe((!arena.asked.is_empty()).then(|| {
+            text(tr_args(
+                "chat-prompt",
+                &[("prompt", arena.asked.as_str().into())],
+            ))
+        }))
+        .push(columns)
+        .push_maybe(arena.winner.map(|_| {
+            button(text(tr("arena-continue")))
+                .on_press(Message::ContinueArenaWinner)
+                .padding([5, 10])
+        }))
+        .spacing(10)
+        .padding(20)
+        .into()
+    }
+
+    fn library_view(&self) -> Element<'_, Message> {
+        let library = &self.library;
+        let search = library.search.to_lowercase();
+        // Space that queued and running pulls are still going to take.
+        let pending: u64 = self
+            .downloads
+            .iter()
+            .map(|dl| dl.progress.total.saturating_sub(dl.progress.completed))
+            .sum();
+        let free_space = library.free_space.map(|free| free.saturating_sub(pending));
+
+        let header = row![
+            text_input(&tr("library-search"), &library.search)
+                .on_input(Message::LibrarySearchChanged)
+                .padding(5)
+                .width(Length::Fixed(300.0)),
+            text(match free_space {
+                Some(free) => tr_args("library-free", &[("size", format_size(free).into())]),
+                None => tr("library-free-unknown"),
+            }),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center);
+
+        let models = library
+            .catalog
+            .models
+            .iter()
+            .filter(|m| {
+                search.is_empty()
+                    || m.name.to_lowercase().contains(&search)
+                    || m.description.to_lowercase().contains(&search)
+            })
+            .map(|m| {
+                let tags = row(m.tags.iter().map(|tag| {
+                    let full_name = format!("{}:{}", m.name, tag.name);
+                    let installed = self.model_infos().iter().any(|m| m.name == full_name);
+                    let queued = self
+                        .downloads
+                        .iter()
+                        .any(|dl| dl.progress.model == full_name);
+                    let fits = free_space.is_none_or(|free| tag.size < free);
+                    let label = if installed {
+                        format!("{} ✓", tag.name)
+                    } else if !fits {
+                        tr_args(
+                            "library-tag-no-space",
+                            &[
+                                ("tag", tag.name.as_str().into()),
+                                ("size", format_size(tag.size).into()),
+                            ],
+                        )
+                    } else {
+                        format!("{} ({})", tag.name, format_size(tag.size))
+                    };
+                    button(text(label).size(12))
+                        .on_press_maybe(
+                            (!installed && !queued && fits)
+                                .then(|| Message::PullLibraryModel(full_name)),
+                        )
+                        .into()
+                }))
+                .spacing(5)
+                .wrap();
+                column![text(&m.name).size(20), text(&m.description).size(14), tags]
+                    .spacing(5)
+                    .padding(5)
+                    .into()
+            });
+
+        let import = row![
+            text_input(&tr("library-path"), &library.import_path)
+                .on_input(Message::LibraryImportPathChanged)
+                .on_submit(Message::ImportLibrary)
+                .padding(5)
+                .width(Length::Fixed(300.0)),
+            button(text(tr("library-import"))).on_press(Message::ImportLibrary),
+            text(tr_args(
+                "library-catalog-date",
+                &[("date", library.catalog.updated.as_str().into())],
+            ))
+            .size(12),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center);
+
+        column![
+            header,
+            scrollable(column(models).spacing(10)).height(Length::Fill),
+            column(self.downloads.iter().map(DownloadJob::view)).spacing(10),
+            import,
+        ]
+        .push_maybe(library.error.as_ref().map(text))
+        .spacing(10)
+        .padding(10)
+        .into()
+    }
+
+    fn model_editor_view(&self) -> Element<'_, Message> {
+        let editor = &self.model_editor;
+        let errors = editor.errors();
+        let field = |label: String, content, on_action: fn(text_editor::Action) -> Message| {
+            column![
+                text(label).size(16),
+                text_editor(content)
+                    .on_action(on_action)
+                    .height(Length::Fixed(140.0))
+                    .padding(5)
+            ]
+            .spacing(5)
+        };
+
+        let form = column![
+            text(tr("model-editor-name")).size(16),
+            text_input(&tr("model-editor-name-placeholder"), &editor.name)
+                .on_input(Message::ModelEditorNameChanged)
+                .padding(5),
+            text(tr("model-editor-from")).size(16),
+            iced::widget::pick_list(
+                self.model_names(),
+                editor.from.clone(),
+                Message::ModelEditorFromChanged
+            )
+            .placeholder(tr("model-editor-base-model"))
+            .padding([5, 10]),
+            field(
+                tr("model-editor-system"),
+                &editor.system,
+                Message::ModelEditorSystemAction
+            ),
+            field(
+                tr("model-editor-template"),
+                &editor.template,
+                Message::ModelEditorTemplateAction
+            ),
+            field(
+                tr("model-editor-parameters"),
+                &editor.parameters,
+                Message::ModelEditorParametersAction
+            ),
+            button(text(tr("model-editor-create")))
+                .on_press_maybe(errors.is_empty().then_some(Message::CreateModel))
+                .padding([5, 10]),
+            column(editor.creating.iter().map(|job| {
+                progress_row(
+                    &job.progress,
+                    &tr("model-editor-creating"),
+                    Message::DismissCreate(job.progress.id),
+                )
+            }))
+            .spacing(5),
+        ]
+        .spacing(10)
+        .width(Length::FillPortion(1));
+
+        let preview = column![
+            text(tr("model-editor-modelfile")).size(16),
+            text(editor.modelfile().to_text())
+                .font(self.fonts.monospace_font())
+                .size(12),
+            column(
+                errors
+                    .into_iter()
+                    .map(|e| text(e).style(text::danger).into())
+            )
+            .spacing(5),
+        ]
+        .spacing(10)
+        .width(Length::FillPortion(1));
+
+        scrollable(row![form, preview].spacing(20).padding(10))
+            .height(Length::Fill)
+            .into()
+    }
+
+    fn statistics_view(&self) -> Element<'_, Message> {
+        let mut per_model: Vec<(String, StatsSummary)> = Vec::new();
+        let mut per_chat = Vec::new();
+
+        for chat in &self.chats {
+            let mut chat_summary = StatsSummary::default();
+            for entry in &chat.chat_entries {
+                let Some(stats) = &entry.stats else {
+                    continue;
+                };
+                let entry_model = entry.model.as_ref().unwrap_or(&chat.model);
+                chat_summary.add(stats);
+                match per_model.iter_mut().find(|(model, _)| model == entry_model) {
+                    Some((_, summary)) => summary.add(stats),
+                    None => {
+                        let mut summary = StatsSummary::default();
+                        summary.add(stats);
+                        per_model.push((entry_model.clone(), summary));
                     }
                 }
             }
+            if chat_summary.responses > 0 {
+                per_chat.push((chat.display_name.clone(), chat_summary));
+            }
         }
+        per_model.sort_by(|a, b| a.0.cmp(&b.0));
 
-        let initial_chat = if chats.is_empty() {
-            OllamaChat::new("llama3.2:latest".to_string())
-        } else {
-            chats[0].clone()
+        let header = |label: &str| -> Element<Message> {
+            row![
+                text(label.to_string())
+                    .size(16)
+                    .width(Length::FillPortion(3)),
+                text(tr("statistics-responses"))
+                    .size(16)
+                    .width(Length::FillPortion(1)),
+                text(tr("statistics-tokens"))
+                    .size(16)
+                    .width(Length::FillPortion(1)),
+                text(tr("statistics-tokens-per-second"))
+                    .size(16)
+                    .width(Length::FillPortion(1)),
+                text(tr("statistics-first-token"))
+                    .size(16)
+                    .width(Length::FillPortion(1)),
+                text(tr("statistics-load"))
+                    .size(16)
+                    .width(Length::FillPortion(1)),
+            ]
+            .spacing(10)
+            .into()
         };
 
-        let mut gui = Self {
-            current_chat: initial_chat.uuid,
-            chats: if chats.is_empty() {
-                vec![initial_chat]
-            } else {
-                chats
-            },
-            editing_chat: None,
-            state: AppState::Chat,
-            default_url: settings.default_url,
-            theme,
-            download_model_input: String::new(),
-            download_progress: Vec::new(),
-            local_models: Vec::new(),
-            selected_model: settings.selected_model,
-        };
-        gui.load_local_models();
-        gui
+        scrollable(
+            column![
+                text(tr("statistics-per-model")).size(24),
+                header(&tr("statistics-model")),
+                column(per_model.iter().map(|(model, s)| s.view(model))).spacing(5),
+                text(tr("statistics-per-chat")).size(24),
+                header(&tr("statistics-chat")),
+                column(per_chat.iter().map(|(name, s)| s.view(name))).spacing(5),
+            ]
+            .spacing(10)
+            .padding(10),
+        )
+        .height(Length::Fill)
+        .into()
     }
 
-    pub fn update(&mut self, message: Message) {
-        match message {
-            Message::NewChat => {
-                let new_chat = OllamaChat::new(self.selected_model.clone());
-                self.current_chat = new_chat.uuid;
-                self.chats.push(new_chat);
-            }
-            Message::StartChat(id) => {
-                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
-                    chat.start();
-                }
-            }
-            Message::ChatProgress((id, progress)) => {
-                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
-                    chat.progress(progress);
-                }
-            }
-            Message::SelectChat(id) => {
-                self.current_chat = id;
-                self.editing_chat = None;
-            }
-            Message::PromptChanged(id, new_prompt) => {
-                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == id) {
-                    chat.input_prompt = new_prompt;
-                }
-            }
-            Message::StartRenameChat(uuid) => {
-                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == uuid) {
-                    chat.start_rename();
-                    self.editing_chat = Some(uuid);
-                }
-            }
-            Message::UpdateTempName(uuid, name) => {
-                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == uuid) {
-                    chat.update_temp_name(name);
-                }
-            }
-            Message::FinishRenameChat(uuid) => {
-                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == uuid) {
-                    chat.finish_rename();
-                    self.editing_chat = None;
-                }
-            }
-            Message::CancelRenameChat(uuid) => {
-                if let Some(chat) = self.chats.iter_mut().find(|c| c.uuid == uuid) {
-                    chat.cancel_rename();
-                    self.editing_chat = None;
-                }
-            }
-            Message::DeleteChat(uuid) => {
-                self.chats.retain(|c| c.uuid != uuid);
-                if self.current_chat == uuid {
-                    self.current_chat = self.chats.first().map(|c| c.uuid).unwrap_or(Uuid::nil());
-                }
-                let _ = fs::remove_file(format!("./chats/{}.json", uuid));
-            }
-            Message::ChangeAppState(app_state) => self.state = app_state,
-            Message::ChangeTheme(theme) => {
-                self.theme = theme;
-                self.save_settings();
-            }
-            Message::ChangeDefaultUrl(url) => {
-                self.default_url = url;
-                self.save_settings();
-            }
-            Message::DownloadModelInputChanged(input) => {
-                self.download_model_input = input;
-            }
-            Message::StartDownloadModel => {
-                if !self.download_model_input.is_empty() {
-                    self.download_progress.push(DownloadProgress {
-                        id: Uuid::new_v4(),
-                        model: self.download_model_input.clone(),
-                        status: "Starting download...".into(),
-                        total: 0,
-                        completed: 0,
-                    });
-                    self.download_model_input.clear();
-                }
-            }
-            Message::DownloadProgress(id, result) => match result {
-                Ok(DownloadProgressUpdate::Progress {
-                    status,
-                    total,
-                    completed,
-                }) => {
-                    if let Some(dl) = self.download_progress.iter_mut().find(|d| d.id == id) {
-                        dl.status = status;
-                        dl.total = total;
-                        dl.completed = completed;
-                    }
-                }
-                Ok(DownloadProgressUpdate::Finished) => {
-                    self.download_progress.retain(|dl| dl.id != id);
-                }
-                Err(e) => {
-                    if let Some(dl) = self.download_progress.iter_mut().find(|d| d.id == id) {
-                        dl.status = format!("Error: {:?}", e);
-                    }
-                }
+    /// Updates `server`'s health with the outcome of a check or a failed send.
+    fn record_health(&mut self, server: Uuid, result: Result<String, Error>) {
+        let previous = self.health.get(&server).cloned().unwrap_or_default();
+        let health = match result {
+            Ok(version) => ServerHealth::Connected { version },
+            Err(e) => ServerHealth::Disconnected {
+                error: e.to_string(),
+                failures: match previous {
+                    ServerHealth::Disconnected { failures, .. } => failures + 1,
+                    _ => 1,
+                },
             },
-            Message::CancelDownload(id) => {
-                self.download_progress.retain(|dl| dl.id != id);
+        };
+        // Log changes only; the check runs every few seconds.
+        match (&previous, &health) {
+            (ServerHealth::Disconnected { .. }, ServerHealth::Disconnected { .. }) => {}
+            (_, ServerHealth::Disconnected { error, .. }) => {
+                tracing::warn!(server = %server, error, "server unreachable")
             }
-            Message::ChangeSelectedModel(model) => {
-                self.selected_model = model;
-                self.save_settings();
+            (ServerHealth::Connected { .. }, ServerHealth::Connected { .. }) => {}
+            (_, ServerHealth::Connected { version }) => {
+                tracing::info!(server = %server, version, "server connected")
             }
-
-            Message::RefreshModels => {
-                self.load_local_models();
+            _ => {}
+        }
+        // Resend queued prompts once, when the server comes back.
+        if previous.is_offline() && !health.is_offline() {
+            for chat in self.chats.iter_mut().filter(|c| c.server == server) {
+                chat.resume_queued();
             }
         }
+        self.health.insert(server, health);
     }
 
-    pub fn theme(&self) -> iced::Theme {
-        self.theme.clone()
-    }
-
-    pub fn subscription(&self) -> Subscription<Message> {
-        let chat_subs = self
-            .chats
-            .iter()
-            .map(|chat| chat.subscription(self.default_url.clone()));
-
-        let download_subs = self.download_progress.iter().map(|dl| {
-            subscribe_to_download(
-                dl.id,
-                format!("{}/api/pull", self.default_url),
-                dl.model.clone(),
-            )
-            .map(|(id, result)| Message::DownloadProgress(id, result))
-        });
-
-        Subscription::batch(chat_subs.chain(download_subs))
+    /// How each server answered its last health check.
+    fn server_status(&self, server: &ServerProfile) -> String {
+        match self.health(server.id) {
+            ServerHealth::Unknown => tr("diagnostics-not-checked"),
+            ServerHealth::Connected { version } => version,
+            ServerHealth::Disconnected { error, failures } => tr_args(
+                "diagnostics-unreachable",
+                &[("failures", failures.into()), ("error", error.into())],
+            ),
+        }
     }
 
-    pub fn view(&self) -> Element<Message> {
-        let top_nav = row![
-            button("Chats")
-                .on_press(Message::ChangeAppState(AppState::Chat))
-                .padding([5, 10])
-                .width(Length::Shrink),
-            button("Settings")
-                .on_press(Message::ChangeAppState(AppState::Settings))
-                .padding([5, 10])
-                .width(Length::Shrink),
-        ]
-        .spacing(5)
-        .padding([0, 5])
-        .align_y(Alignment::Start);
+    fn diagnostics_view(&self) -> Element<'_, Message> {
+        let servers = column(self.servers.iter().map(|server| {
+            row![
+                text(server.name.clone()).width(Length::FillPortion(1)),
+                text(redact_url(&server.url)).width(Length::FillPortion(2)),
+                text(self.server_status(server)).width(Length::FillPortion(3)),
+            ]
+            .spacing(10)
+            .into()
+        }))
+        .spacing(5);
 
-        match self.state {
-            AppState::Chat => {
-                let sidebar_chats = scrollable(
-                    column(self.chats.iter().map(|chat| {
-                        chat.sidebar_view(
-                            chat.uuid == self.current_chat,
-                            self.editing_chat == Some(chat.uuid),
-                        )
-                    }))
-                    .spacing(5),
-                )
-                .spacing(5)
-                .width(Length::Fill)
-                .height(Length::Fill);
+        let events = column(
+            diagnostics::recent()
+                .iter()
+                .rev()
+                .take(MAX_DIAGNOSTICS_EVENTS)
+                .map(|entry| {
+                    let line = text(entry.to_string())
+                        .font(self.fonts.monospace_font())
+                        .size(12);
+                    match entry.level {
+                        tracing::Level::ERROR => line.style(text::danger),
+                        tracing::Level::WARN => line.style(text::primary),
+                        _ => line,
+                    }
+                    .into()
+                }),
+        )
+        .spacing(2);
 
-                let left_sidebar = column![
-                    row![button("New Chat")
-                        .on_press(Message::NewChat)
-                        .padding([5, 10])]
-                    .padding([5, 0]),
-                    sidebar_chats
+        scrollable(
+            column![
+                text(tr("nav-diagnostics")).size(24),
+                row![
+                    button(text(tr("diagnostics-copy"))).on_press(Message::CopyDiagnostics),
+                    text(tr("diagnostics-redacted")).size(12),
                 ]
-                .spacing(5)
-                .width(Length::FillPortion(1));
-
-                let current_chat = self
-                    .chats
-                    .iter()
-                    .find(|c| c.uuid == self.current_chat)
-                    .map(|chat| chat.main_view())
-                    .unwrap_or_else(|| column!().into());
-
-                let main_content = container(current_chat)
-                    .width(Length::FillPortion(4))
-                    .height(Length::Fill)
-                    .align_x(Horizontal::Center);
+                .spacing(10)
+                .align_y(Alignment::Center),
+                text(tr_args(
+                    "diagnostics-logs",
+                    &[("dir", diagnostics::log_dir().into())]
+                ))
+                .size(12),
+                text(tr("diagnostics-servers")).size(16),
+                servers,
+                text(tr("diagnostics-events")).size(16),
+                events,
+            ]
+            .spacing(10)
+            .padding(10),
+        )
+        .height(Length::Fill)
+        .into()
+    }
 
-                column![
-                    top_nav,
-                    row![left_sidebar, main_content]
-                        .spacing(10)
-                        .padding(5)
-                        .height(Length::Fill)
-                ]
-                .into()
-            }
-            AppState::Settings => {
-                let downloads_view = if self.download_progress.is_empty() {
-                    column!().into()
+    /// A plain-text report for bug reports: versions, server status and recent
+    /// events. Chats are left out and every known secret is blanked.
+    fn diagnostics_bundle(&self) -> String {
+        let mut report = format!(
+            "Ollama GUI {} on {} {}\nLog files: {}\n\nServers\n",
+            env!("CARGO_PKG_VERSION"),
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            diagnostics::log_dir()
+        );
+        for server in &self.servers {
+            report.push_str(&format!(
+                "- {}{} ({}, {}, {} extra headers) {}: {}\n",
+                server.name,
+                if server.id == self.active_server {
+                    " [active]"
                 } else {
-                    column(
-                        self.download_progress
-                            .iter()
-                            .map(|dl| -> Element<Message> {
-                                let progress = if dl.total > 0 {
-                                    dl.completed as f32 / dl.total as f32
-                                } else {
-                                    0.0
-                                };
-                                let percentage = if dl.total > 0 {
-                                    (progress * 100.0) as u32
-                                } else {
-                                    0
-                                };
-                                Into::<Element<Message>>::into(
-                                    row![
-                                        Into::<Element<Message>>::into(
-                                            column![
-                                                text(format!(
-                                                    "Downloading {}: {}%",
-                                                    dl.model, percentage
-                                                ))
-                                                .size(16),
-                                                progress_bar::<iced::Theme>(0.0..=1.0, progress)
-                                                    .width(Length::Fixed(300.0)),
-                                                text(&dl.status)
-                                            ]
-                                            .padding([5, 0])
-                                            .spacing(5)
-                                        ),
-                                        button("Cancel")
-                                            .on_press(Message::CancelDownload(dl.id))
-                                            .padding([5, 10])
-                                    ]
-                                    .align_y(Vertical::Center)
-                                    .spacing(10),
-                                )
-                            })
-                            .collect::<Vec<_>>(),
-                    )
-                };
-
-                column![
-                    top_nav,
-                    column![
-                        text("Theme").size(16),
-                        iced::widget::pick_list(
-                            iced::Theme::ALL,
-                            Some(self.theme()),
-                            Message::ChangeTheme
-                        )
-                        .padding([5, 10])
-                        .width(Length::Shrink),
-                        text("Ollama URL").size(16),
-                        text_input("http://localhost:11434", &self.default_url)
-                            .on_input(Message::ChangeDefaultUrl)
-                            .padding(5)
-                            .width(Length::Fixed(300.0)),
-                        text("Select Model").size(16),
-                        iced::widget::pick_list(
-                            &*self.local_models,
-                            Some(&self.selected_model),
-                            Message::ChangeSelectedModel
-                        )
-                        .padding([5, 10])
-                        .width(Length::Shrink),
-                        button("Refresh Models").on_press(Message::RefreshModels),
-                        text("Download Model").size(16),
-                        row![
-                            text_input("Model name", &self.download_model_input)
-                                .on_input(Message::DownloadModelInputChanged)
-                                .padding(5)
-                                .width(Length::Fixed(300.0))
-                                .on_submit_maybe(if self.download_model_input.is_empty() {
-                                    None
-                                } else {
-                                    Some(Message::StartDownloadModel)
-                                }),
-                            button("Download")
-                                .on_press_maybe(if self.download_model_input.is_empty() {
-                                    None
-                                } else {
-                                    Some(Message::StartDownloadModel)
-                                })
-                                .padding([5, 10])
-                        ],
-                        downloads_view
-                    ]
-                    .spacing(10)
-                    .padding(10)
-                    .align_x(Alignment::Start)
-                ]
-                .into()
+                    ""
+                },
+                server.backend,
+                server.auth,
+                server.secrets.headers.len(),
+                redact_url(&server.url),
+                self.server_status(server)
+            ));
+        }
+        report.push_str(&format!(
+            "\nInstalled models: {}\nChats: {}\nDownloads: {}\nAutomation API: {}\n\nRecent events (UTC)\n",
+            self.model_infos().len(),
+            self.chats.len(),
+            self.downloads.len(),
+            if self.api_enabled {
+                format!("port {}", self.api_port)
+            } else {
+                "off".to_string()
             }
+        ));
+        for entry in diagnostics::recent() {
+            report.push_str(&format!("{}\n", entry));
+        }
+
+        let mut secrets: Vec<&str> = self.api_token.iter().map(String::as_str).collect();
+        for server in &self.servers {
+            secrets.push(&server.secrets.token);
+            secrets.push(&server.secrets.password);
+            secrets.extend(
+                server
+                    .secrets
+                    .headers
+                    .iter()
+                    .map(|(_, value)| value.as_str()),
+            );
         }
+        redact(&report, &secrets)
     }
 }
 
@@ -549,65 +4720,200 @@ impl Default for OllamaGUI {
 enum ChatState {
     Idle,
     Streaming,
+    /// The server could not be reached; the last prompt is resent once it is back.
+    Queued,
     Finished,
     Errored,
 }
 
+#[derive(Debug, Clone)]
+enum SummaryTarget {
+    /// Replace the older turns of the same chat with the summary.
+    TrimHistory,
+    /// Start a new chat that carries the summary of this one.
+    NewChat,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
-struct ChatHistory {
+pub(crate) struct ChatHistory {
     display_name: String,
     uuid: String,
     context: Vec<u64>,
     model: String,
     chat: Vec<ChatEntry>,
+    #[serde(default)]
+    server: Uuid,
+    #[serde(default)]
+    num_ctx: Option<u64>,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    context_start: usize,
+    /// Missing in chats saved before titles were generated; those count as
+    /// renamed unless they still have the default name.
+    #[serde(default)]
+    renamed: Option<bool>,
+    /// Unsent text in the prompt editor.
+    #[serde(default)]
+    draft: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct ChatEntry {
     prompt: String,
     response: String,
+    #[serde(default)]
+    stats: Option<GenerationStats>,
+    /// Model that wrote `response`. Missing in chats saved before models could be switched.
+    #[serde(default)]
+    model: Option<String>,
 }
 
-#[derive(Debug, Clone)]
-struct OllamaChat {
-    uuid: Uuid,
-    display_name: String,
+#[derive(Debug)]
+pub(crate) struct OllamaChat {
+    pub(crate) uuid: Uuid,
+    pub(crate) display_name: String,
     editing_name: Option<String>,
     state: ChatState,
-    input_prompt: String,
-    model: String,
+    input: text_editor::Content,
+    /// The draft changed since the chat was last saved.
+    draft_dirty: bool,
+    pub(crate) model: String,
+    /// Id of the `ServerProfile` the chat talks to.
+    pub(crate) server: Uuid,
+    /// Token context returned by Ollama. `None` means the next request replays
+    /// the history from `context_start` as text instead.
     context: Option<Vec<u64>>,
     chat_entries: Vec<ChatEntry>,
+    num_ctx: Option<u64>,
+    /// Text typed into the `num_ctx` field, applied on submit or with the next prompt.
+    editing_num_ctx: Option<String>,
+    summary: Option<String>,
+    /// Index of the first entry that is still part of the model's context.
+    context_start: usize,
+    summarizing: Option<SummaryTarget>,
+    /// Set once the user names the chat, so generated titles never overwrite it.
+    renamed: bool,
+    /// A title request is in flight.
+    titling: bool,
+    /// Why the last reply failed, shown while the chat is `Errored`.
+    error: Option<String>,
+    /// `file_stamp` of the chat file when this copy last read or wrote it.
+    on_disk: Option<FileStamp>,
+    /// Entries the file held at that point; later ones exist only in memory.
+    synced_turns: usize,
+}
+
+/// Modification time and size of a chat file, to notice writes by another
+/// process such as the CLI.
+type FileStamp = (SystemTime, u64);
+
+fn file_stamp(path: &Path) -> Option<FileStamp> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn read_history(path: &Path) -> Result<ChatHistory, String> {
+    let file = fs::File::open(path).map_err(|e| e.to_string())?;
+    serde_json::from_reader(file).map_err(|e| e.to_string())
 }
 
 impl OllamaChat {
-    pub fn new(model: String) -> Self {
+    pub fn new(model: String, server: Uuid) -> Self {
         let uuid = Uuid::new_v4();
         Self {
             uuid,
-            display_name: "New Unnamed Chat".to_string(),
+            display_name: DEFAULT_CHAT_NAME.to_string(),
             editing_name: None,
             state: ChatState::Idle,
-            input_prompt: String::new(),
+            input: text_editor::Content::new(),
+            draft_dirty: false,
             model,
+            server,
             context: None,
             chat_entries: Vec::new(),
+            num_ctx: None,
+            editing_num_ctx: None,
+            summary: None,
+            context_start: 0,
+            summarizing: None,
+            renamed: false,
+            titling: false,
+            error: None,
+            on_disk: None,
+            synced_turns: 0,
+        }
+    }
+
+    /// Every chat saved in `./chats`.
+    #[tracing::instrument(level = "debug")]
+    pub(crate) fn load_all() -> Vec<Self> {
+        let mut chats = Vec::new();
+
+        if let Ok(entries) = fs::read_dir("./chats") {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().map_or(false, |ext| ext == "json") {
+                    if let Ok(file) = fs::File::open(&path) {
+                        let stamp = file_stamp(&path);
+                        match serde_json::from_reader::<_, ChatHistory>(file) {
+                            Ok(history) => match OllamaChat::from_history(history) {
+                                Ok(chat) => chats.push(Self {
+                                    on_disk: stamp,
+                                    ..chat
+                                }),
+                                Err(e) => {
+                                    tracing::error!(file = ?path, error = %e, "invalid chat id")
+                                }
+                            },
+                            Err(e) => {
+                                tracing::error!(file = ?path, error = %e, "could not parse chat")
+                            }
+                        }
+                    }
+                }
+            }
         }
+        chats
     }
 
     pub fn from_history(history: ChatHistory) -> Result<Self, uuid::Error> {
+        let renamed = history
+            .renamed
+            .unwrap_or(history.display_name != DEFAULT_CHAT_NAME);
         Ok(Self {
             uuid: Uuid::parse_str(&history.uuid)?,
             display_name: history.display_name,
             editing_name: None,
             state: ChatState::Finished,
-            input_prompt: String::new(),
+            input: text_editor::Content::with_text(&history.draft),
+            draft_dirty: false,
             model: history.model,
-            context: Some(history.context),
+            server: history.server,
+            context: Some(history.context).filter(|context| !context.is_empty()),
+            context_start: history.context_start.min(history.chat.len()),
+            synced_turns: history.chat.len(),
             chat_entries: history.chat,
+            num_ctx: history.num_ctx,
+            editing_num_ctx: None,
+            summary: history.summary,
+            summarizing: None,
+            renamed,
+            titling: false,
+            error: None,
+            on_disk: None,
         })
     }
 
+    fn input_text(&self) -> String {
+        self.input.text().trim_end().to_string()
+    }
+
+    pub(crate) fn set_input(&mut self, text: &str) {
+        self.input = text_editor::Content::with_text(text);
+        self.draft_dirty = true;
+    }
+
     pub fn start_rename(&mut self) {
         self.editing_name = Some(self.display_name.clone());
     }
@@ -621,6 +4927,7 @@ impl OllamaChat {
     pub fn finish_rename(&mut self) {
         if let Some(name) = self.editing_name.take() {
             self.display_name = name.trim().to_string();
+            self.renamed = true;
             self.save_chat_history();
         }
     }
@@ -629,18 +4936,42 @@ impl OllamaChat {
         self.editing_name = None;
     }
 
+    pub fn edit_num_ctx(&mut self, value: String) {
+        self.editing_num_ctx = Some(value.chars().filter(|c| c.is_ascii_digit()).collect());
+    }
+
+    pub fn finish_num_ctx(&mut self) {
+        if let Some(value) = self.editing_num_ctx.take() {
+            self.num_ctx = value.parse().ok().filter(|n| *n > 0);
+            self.save_chat_history();
+        }
+    }
+
+    /// Sends the prompt in the editor.
     pub fn start(&mut self) {
-        if matches!(
+        self.finish_num_ctx();
+        if self.send(self.input_text()) {
+            self.set_input("");
+        }
+    }
+
+    /// Sends `prompt` without touching the draft in the editor. Returns false
+    /// while the chat cannot take a prompt.
+    pub fn send(&mut self, prompt: String) -> bool {
+        if !matches!(
             self.state,
             ChatState::Idle | ChatState::Finished | ChatState::Errored
         ) {
-            self.chat_entries.push(ChatEntry {
-                prompt: self.input_prompt.clone(),
-                response: String::new(),
-            });
-            self.state = ChatState::Streaming;
-            self.input_prompt.clear();
+            return false;
         }
+        self.chat_entries.push(ChatEntry {
+            prompt,
+            response: String::new(),
+            stats: None,
+            model: Some(self.model.clone()),
+        });
+        self.state = ChatState::Streaming;
+        true
     }
 
     pub fn progress(&mut self, progress: Result<OllamaStreamProgress, Error>) {
@@ -651,46 +4982,293 @@ impl OllamaChat {
                         last_entry.response.push_str(&token);
                     }
                 }
-                Ok(OllamaStreamProgress::Finished { context }) => {
-                    self.context = Some(context);
+                Ok(OllamaStreamProgress::Finished { context, stats }) => {
+                    if let Some(last_entry) = self.chat_entries.last_mut() {
+                        last_entry.stats = Some(stats);
+                    }
+                    // Backends without token contexts replay the history instead.
+                    self.context = Some(context).filter(|context| !context.is_empty());
                     self.state = ChatState::Finished;
                     self.save_chat_history();
                 }
-                Err(_) => {
-                    self.state = ChatState::Errored;
+                Err(e) => {
+                    let unanswered = self
+                        .chat_entries
+                        .last()
+                        .is_some_and(|entry| entry.response.is_empty());
+                    self.state = if unanswered && e.is_connection_error() {
+                        ChatState::Queued
+                    } else {
+                        self.error = Some(e.to_string());
+                        ChatState::Errored
+                    };
                 }
             }
         }
     }
 
-    fn save_chat_history(&self) {
-        let file_path = format!("./chats/{}.json", self.uuid);
+    /// Switches the model for the following prompts. The old model's token context
+    /// means nothing to the new one, so the transcript is replayed instead.
+    pub fn change_model(&mut self, model: String) {
+        let busy = self.summarizing.is_some()
+            || matches!(self.state, ChatState::Streaming | ChatState::Queued);
+        if !busy && model != self.model {
+            self.model = model;
+            self.context = None;
+            self.save_chat_history();
+        }
+    }
+
+    /// Ends a streaming reply early, keeping the text received so far.
+    pub fn stop(&mut self) {
+        if let ChatState::Streaming = self.state {
+            // The reply never finished, so no token context covers it.
+            self.context = None;
+            self.state = ChatState::Finished;
+            self.save_chat_history();
+        }
+    }
+
+    /// Asks for the last response again with the chat's current model.
+    pub fn regenerate(&mut self) {
+        if self.summarizing.is_some()
+            || !matches!(self.state, ChatState::Finished | ChatState::Errored)
+        {
+            return;
+        }
+        if let Some(last_entry) = self.chat_entries.last_mut() {
+            last_entry.response.clear();
+            last_entry.stats = None;
+            last_entry.model = Some(self.model.clone());
+            // The stored context already includes the old answer.
+            self.context = None;
+            self.state = ChatState::Streaming;
+        }
+    }
+
+    fn is_queued(&self) -> bool {
+        matches!(self.state, ChatState::Queued)
+    }
+
+    /// Sends the queued prompt again.
+    pub fn resume_queued(&mut self) {
+        if self.is_queued() {
+            self.state = ChatState::Streaming;
+        }
+    }
+
+    pub fn cancel_queued(&mut self) {
+        if self.is_queued() {
+            self.error = None;
+            self.state = ChatState::Errored;
+        }
+    }
+
+    /// Number of tokens currently in the model's context and whether the value is
+    /// an estimate (after trimming there is no token context until the next reply).
+    fn context_usage(&self) -> (u64, bool) {
+        match &self.context {
+            Some(context) => (context.len() as u64, false),
+            None => ((self.replay_text().len() / 4) as u64, true),
+        }
+    }
+
+    /// Finished turns from `context_start`, excluding the entry being answered.
+    fn history(&self) -> Vec<(String, String)> {
+        let end = self.chat_entries.len().saturating_sub(1);
+        self.chat_entries[self.context_start.min(end)..end]
+            .iter()
+            .map(|entry| (entry.prompt.clone(), entry.response.clone()))
+            .collect()
+    }
+
+    /// Earlier turns and the carried summary as plain text, used when the token
+    /// context has been discarded.
+    fn replay_text(&self) -> String {
+        transcript(self.summary.as_deref(), &self.history())
+    }
+
+    pub(crate) fn chat_request(&self) -> ChatRequest {
+        ChatRequest {
+            model: self.model.clone(),
+            prompt: self.chat_entries.last().unwrap().prompt.clone(),
+            history: self.history(),
+            summary: self.summary.clone(),
+            context: self.context.clone(),
+            num_ctx: self.num_ctx,
+        }
+    }
+
+    fn drop_oldest_turn(&mut self) {
+        if self.context_start < self.chat_entries.len() {
+            self.context_start += 1;
+            self.context = None;
+            self.save_chat_history();
+        }
+    }
+
+    fn start_summary(&mut self, target: SummaryTarget) {
+        if self.summarizing.is_none() && !matches!(self.state, ChatState::Streaming) {
+            self.summarizing = Some(target);
+        }
+    }
+
+    fn title_prompt(&self) -> String {
+        let first = self.chat_entries.first();
+        let excerpt = |text: &str| text.chars().take(1000).collect::<String>();
+        format!(
+            "Write a short title of at most six words for a conversation that starts \
+             with the exchange below. Reply with the title only.\n\nUser: {}\nAssistant: {}",
+            excerpt(first.map(|e| e.prompt.as_str()).unwrap_or_default()),
+            excerpt(first.map(|e| e.response.as_str()).unwrap_or_default())
+        )
+    }
+
+    fn summary_prompt(&self) -> String {
+        let mut prompt = String::from(
+            "Summarize the following conversation in a few short paragraphs. \
+             Keep names, decisions, facts and open questions. Reply with the summary only.\n\n",
+        );
+        if let Some(summary) = &self.summary {
+            prompt.push_str(&format!("Earlier summary:\n{}\n\n", summary));
+        }
+        for entry in &self.chat_entries[self.context_start.min(self.chat_entries.len())..] {
+            prompt.push_str(&format!(
+                "User: {}\nAssistant: {}\n\n",
+                entry.prompt, entry.response
+            ));
+        }
+        prompt
+    }
+
+    /// Replaces every turn so far with `summary`, which already covers them.
+    fn apply_summary(&mut self, summary: String) {
+        self.summary = Some(summary);
+        self.context_start = self.chat_entries.len();
+        self.context = None;
+        self.save_chat_history();
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(chat = %self.uuid))]
+    pub(crate) fn save_chat_history(&mut self) {
+        let draft_dirty = std::mem::take(&mut self.draft_dirty);
+        let file_path = PathBuf::from(format!("./chats/{}.json", self.uuid));
         let _ = fs::create_dir_all("./chats");
 
-        let chat_history = ChatHistory {
+        let stamp = file_stamp(&file_path);
+        if stamp.is_some() && stamp != self.on_disk {
+            match read_history(&file_path) {
+                Ok(history) => self.merge_from_disk(history, draft_dirty),
+                Err(e) => tracing::error!(error = %e, "could not reread changed chat"),
+            }
+        }
+
+        // Written next to the chat and renamed over it, so a reader never sees
+        // half a file.
+        let temp_path = file_path.with_extension("json.tmp");
+        let result = fs::File::create(&temp_path)
+            .map_err(|e| e.to_string())
+            .and_then(|file| {
+                serde_json::to_writer_pretty(file, &self.to_history()).map_err(|e| e.to_string())
+            })
+            .and_then(|_| fs::rename(&temp_path, &file_path).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => {
+                self.on_disk = file_stamp(&file_path);
+                self.synced_turns = self.chat_entries.len();
+            }
+            Err(e) => {
+                let _ = fs::remove_file(&temp_path);
+                tracing::error!(error = %e, "could not save chat");
+            }
+        }
+    }
+
+    /// Takes in turns another process appended to the file since this copy read
+    /// it, keeping the turns added here after them. Everything else stays as it
+    /// is here, except a draft that was not touched.
+    fn merge_from_disk(&mut self, history: ChatHistory, draft_dirty: bool) {
+        let local = self
+            .chat_entries
+            .split_off(self.synced_turns.min(self.chat_entries.len()));
+        if history.chat.len() != self.chat_entries.len() {
+            // The token context no longer covers every turn.
+            self.context = None;
+        }
+        self.chat_entries = history.chat;
+        self.chat_entries.extend(local);
+        self.context_start = self.context_start.min(self.chat_entries.len());
+        if !draft_dirty {
+            self.input = text_editor::Content::with_text(&history.draft);
+        }
+    }
+
+    /// The conversation as Markdown, one section per turn.
+    pub(crate) fn to_markdown(&self) -> String {
+        let mut text = format!("# {}\n\n", self.display_name);
+        for entry in &self.chat_entries {
+            text.push_str(&format!(
+                "## You\n\n{}\n\n## {}\n\n{}\n\n",
+                entry.prompt,
+                entry.model.as_deref().unwrap_or(&self.model),
+                entry.response
+            ));
+        }
+        text
+    }
+
+    /// The chat in the format of its `./chats` file.
+    pub(crate) fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.to_history()).unwrap()
+    }
+
+    pub(crate) fn turns(&self) -> usize {
+        self.chat_entries.len()
+    }
+
+    fn to_history(&self) -> ChatHistory {
+        ChatHistory {
             display_name: self.display_name.clone(),
             uuid: self.uuid.to_string(),
             context: self.context.clone().unwrap_or_default(),
             model: self.model.clone(),
             chat: self.chat_entries.clone(),
-        };
-
-        if let Ok(file) = fs::File::create(&file_path) {
-            let _ = serde_json::to_writer_pretty(file, &chat_history);
+            server: self.server,
+            num_ctx: self.num_ctx,
+            summary: self.summary.clone(),
+            context_start: self.context_start,
+            renamed: Some(self.renamed),
+            draft: self.input_text(),
         }
     }
 
-    pub fn subscription(&self, base_url: String) -> Subscription<Message> {
+    pub fn subscription(
+        &self,
+        backend: &dyn ChatBackend,
+        client: Result<reqwest::Client, String>,
+        base_url: &str,
+    ) -> Subscription<Message> {
         if let ChatState::Streaming = self.state {
-            let api_url = format!("{}/api/generate", base_url);
-            subscribe_to_stream(
-                self.uuid,
-                api_url,
-                &self.chat_entries.last().unwrap().prompt,
-                &self.model,
-                self.context.clone(),
-            )
+            match client {
+                Ok(client) => {
+                    subscribe_to_stream(self.uuid, backend, client, base_url, self.chat_request())
+                }
+                Err(e) => subscribe_to_client_error(self.uuid, e),
+            }
             .map(Message::ChatProgress)
+        } else if self.summarizing.is_some() {
+            match client {
+                Ok(client) => subscribe_to_completion(
+                    (self.uuid, "summary"),
+                    backend,
+                    client,
+                    base_url,
+                    self.model.clone(),
+                    self.summary_prompt(),
+                ),
+                Err(e) => subscribe_to_client_error((self.uuid, "summary"), e),
+            }
+            .map(|((id, _), result)| Message::SummaryFinished(id, result))
         } else {
             Subscription::none()
         }
@@ -701,7 +5279,7 @@ impl OllamaChat {
 
         let controls = if is_editing {
             row![
-                text_input("Chat Name", current_name)
+                text_input(&tr("sidebar-chat-name"), current_name)
                     .on_input(|s| Message::UpdateTempName(self.uuid, s))
                     .on_submit(Message::FinishRenameChat(self.uuid))
                     .padding(5)
@@ -716,6 +5294,10 @@ impl OllamaChat {
         } else {
             row![
                 text(current_name).width(Length::Fill),
+                button(if self.titling { "…" } else { "⟳" }).on_press_maybe(
+                    (!self.titling && !self.chat_entries.is_empty())
+                        .then_some(Message::RegenerateTitle(self.uuid))
+                ),
                 button("✎").on_press(Message::StartRenameChat(self.uuid)),
                 button("🗑").on_press(Message::DeleteChat(self.uuid))
             ]
@@ -725,6 +5307,7 @@ impl OllamaChat {
         let status_icon = match self.state {
             ChatState::Idle => text("●"),
             ChatState::Streaming => text("↻"),
+            ChatState::Queued => text("⏸"),
             ChatState::Finished => text("✓"),
             ChatState::Errored => text("⚠"),
         };
@@ -744,22 +5327,115 @@ impl OllamaChat {
         }
     }
 
-    fn main_view(&self) -> Element<Message> {
+    fn context_meter(&self, context_length: Option<u64>) -> Element<'_, Message> {
+        let (used, estimated) = self.context_usage();
+        let num_ctx = match &self.editing_num_ctx {
+            Some(value) => value.clone(),
+            None => self.num_ctx.map(|n| n.to_string()).unwrap_or_default(),
+        };
+        let num_ctx_input = text_input(&tr("context-num-ctx"), &num_ctx)
+            .on_input(|s| Message::ChangeNumCtx(self.uuid, s))
+            .on_submit(Message::SubmitNumCtx(self.uuid))
+            .padding(5)
+            .width(Length::Fixed(100.0));
+        let approx = if estimated { "~" } else { "" };
+
+        let Some(limit) = context_length else {
+            return row![
+                text(tr_args(
+                    "context-tokens",
+                    &[("approx", approx.into()), ("used", used.into())]
+                ))
+                .size(12),
+                num_ctx_input
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .into();
+        };
+
+        let fill = (used as f32 / limit.max(1) as f32).min(1.0);
+        let busy = self.summarizing.is_some() || matches!(self.state, ChatState::Streaming);
+        let strategies = (fill >= 0.75).then(|| {
+            row![
+                button(text(tr("context-drop-oldest")).size(12))
+                    .on_press_maybe((!busy).then_some(Message::DropOldestTurn(self.uuid))),
+                button(text(tr("context-summarize")).size(12))
+                    .on_press_maybe((!busy).then_some(Message::SummarizeHistory(self.uuid))),
+                button(text(tr("context-continue")).size(12))
+                    .on_press_maybe((!busy).then_some(Message::ContinueInNewChat(self.uuid))),
+            ]
+            .spacing(5)
+        });
+
+        column![row![
+            progress_bar::<iced::Theme>(0.0..=1.0, fill)
+                .height(Length::Fixed(8.0))
+                .width(Length::Fill),
+            text(tr_args(
+                "context-usage",
+                &[
+                    ("approx", approx.into()),
+                    ("used", used.into()),
+                    ("limit", limit.into()),
+                    ("percent", format!("{:.0}", fill * 100.0).into()),
+                ]
+            ))
+            .size(12),
+            num_ctx_input
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center)]
+        .push_maybe(strategies)
+        .push_maybe(
+            self.summarizing
+                .is_some()
+                .then(|| text(tr("context-summarizing")).size(12)),
+        )
+        .spacing(5)
+        .into()
+    }
+
+    fn main_view(
+        &self,
+        context_length: Option<u64>,
+        offline: bool,
+        models: Vec<String>,
+        templates: &[PromptTemplate],
+        send_key: SendKey,
+        monospace: iced::Font,
+    ) -> Element<'_, Message> {
         let chat_log = scrollable(
             column(
                 self.chat_entries
                     .iter()
                     .map(|entry| {
                         column![
-                            text(format!("Prompt: {}", entry.prompt)),
+                            text(tr_args(
+                                "chat-prompt",
+                                &[("prompt", entry.prompt.as_str().into())]
+                            )),
                             // iced::widget::TextInput::new(
                             //     "",
                             //     &format!("{}: {}", self.model, entry.response)
                             // )
                             // .width(Length::Fill)
                             // .style(borderless_input_style()),
-                            text(format!("{}: {}", self.model, entry.response)).width(Length::Fill)
+                            response_view(
+                                format!(
+                                    "{}: {}",
+                                    entry.model.as_ref().unwrap_or(&self.model),
+                                    entry.response
+                                ),
+                                monospace
+                            )
                         ]
+                        .push_maybe(
+                            entry
+                                .stats
+                                .as_ref()
+                                .map(|stats| text(stats.footer()).size(12)),
+                        )
                         .spacing(5)
                         .padding(10)
                         .into()
@@ -771,26 +5447,119 @@ impl OllamaChat {
         .height(Length::Fill);
 
         let on_submit_message = match self.state {
-            ChatState::Streaming => None,
+            ChatState::Streaming | ChatState::Queued => None,
+            _ if offline => None,
             _ => Some(Message::StartChat(self.uuid)),
         };
 
-        let input_row = row![
-            text_input("Type your prompt...", &self.input_prompt)
-                .on_input(|s| Message::PromptChanged(self.uuid, s))
-                .on_submit_maybe(on_submit_message)
-                .padding(10)
-                .width(Length::Fill),
-            match self.state {
-                ChatState::Idle | ChatState::Finished =>
-                    button("Send").on_press(Message::StartChat(self.uuid)),
-                ChatState::Streaming => button("Stop").on_press(Message::SelectChat(self.uuid)),
-                ChatState::Errored => button("Retry").on_press(Message::StartChat(self.uuid)),
-            }
+        let prompt_picker =
+            iced::widget::pick_list(templates.to_vec(), None::<PromptTemplate>, |t| {
+                Message::InsertPrompt(self.uuid, t)
+            })
+            .placeholder(tr("chat-prompts"))
+            .padding(10);
+
+        // Typing `/` lists the templates whose command matches.
+        let input = self.input_text();
+        let suggestions = input
+            .strip_prefix('/')
+            .filter(|typed| !typed.contains(char::is_whitespace))
+            .map(|typed| {
+                row(templates
+                    .iter()
+                    .filter(|t| !t.command.is_empty() && t.command.starts_with(typed))
+                    .map(|t| {
+                        button(text(format!("/{} · {}", t.command, t.name)).size(12))
+                            .on_press(Message::InsertPrompt(self.uuid, t.clone()))
+                            .padding([3, 8])
+                            .into()
+                    }))
+                .spacing(5)
+                .wrap()
+            });
+
+        let input_row =
+            row![
+                prompt_picker,
+                container(
+                    text_editor(&self.input)
+                        .placeholder(tr("chat-input-placeholder"))
+                        .on_action(|action| Message::PromptAction(self.uuid, action))
+                        .key_binding(move |key_press| {
+                            let is_enter = matches!(
+                                key_press.key.as_ref(),
+                                keyboard::Key::Named(keyboard::key::Named::Enter)
+                            );
+                            let sends = match send_key {
+                                SendKey::Enter => !key_press.modifiers.shift(),
+                                SendKey::ShiftEnter => key_press.modifiers.shift(),
+                            };
+                            // Ctrl/Cmd with a letter is a shortcut, never typed text.
+                            let is_shortcut = key_press.modifiers.command()
+                                && !matches!(
+                                    key_press.key.as_ref(),
+                                    keyboard::Key::Character("c" | "x" | "v" | "a")
+                                        | keyboard::Key::Named(_)
+                                );
+                            if is_enter && key_press.status == text_editor::Status::Focused {
+                                if sends {
+                                    on_submit_message.clone().map(text_editor::Binding::Custom)
+                                } else {
+                                    Some(text_editor::Binding::Enter)
+                                }
+                            } else if is_shortcut {
+                                None
+                            } else {
+                                text_editor::Binding::from_key_press(key_press)
+                            }
+                        })
+                        .padding(10)
+                        .height(Length::Fixed(
+                            self.input.line_count().clamp(1, MAX_PROMPT_LINES) as f32 * 21.0 + 20.0
+                        ))
+                )
+                .id(container::Id::new(PROMPT_INPUT)),
+                match self.state {
+                    ChatState::Idle | ChatState::Finished => button(text(tr("chat-send")))
+                        .on_press_maybe((!offline).then_some(Message::StartChat(self.uuid))),
+                    ChatState::Streaming =>
+                        button(text(tr("chat-stop"))).on_press(Message::StopChat(self.uuid)),
+                    ChatState::Queued => button(text(tr("chat-cancel")))
+                        .on_press(Message::CancelQueuedPrompt(self.uuid)),
+                    ChatState::Errored => button(text(tr("chat-retry")))
+                        .on_press_maybe((!offline).then_some(Message::StartChat(self.uuid))),
+                }
+            ]
+            .spacing(10);
+
+        let status = match self.state {
+            ChatState::Queued => Some(tr("chat-queued")),
+            ChatState::Errored if self.error.is_some() => self.error.clone(),
+            _ if offline => Some(tr("chat-offline")),
+            _ => None,
+        };
+
+        let busy = self.summarizing.is_some()
+            || matches!(self.state, ChatState::Streaming | ChatState::Queued);
+        let model_picker = iced::widget::pick_list(models, Some(self.model.clone()), |model| {
+            Message::ChangeChatModel(self.uuid, model)
+        })
+        .padding([5, 10]);
+        let header = row![
+            text(&self.display_name).size(24).width(Length::Fill),
+            if busy {
+                Element::from(text(&self.model))
+            } else {
+                model_picker.into()
+            },
         ]
-        .spacing(10);
+        .spacing(10)
+        .align_y(Alignment::Center);
 
-        column![text(&self.display_name).size(24), chat_log, input_row]
+        column![header, chat_log, self.context_meter(context_length),]
+            .push_maybe(status.map(|status| text(status).style(text::danger)))
+            .push_maybe(suggestions)
+            .push(input_row)
             .spacing(20)
             .padding(20)
             .height(Length::Fill)
@@ -798,6 +5567,162 @@ impl OllamaChat {
     }
 }
 
+/// Sends `line` to every API client waiting on `chat`; `done` ends their replies.
+/// Clients that hung up are dropped.
+fn notify_api(
+    listeners: &mut HashMap<Uuid, Vec<ApiReply>>,
+    chat: Uuid,
+    line: serde_json::Value,
+    done: bool,
+) {
+    let Some(replies) = listeners.get_mut(&chat) else {
+        return;
+    };
+    replies.retain(|reply| {
+        reply
+            .unbounded_send(ApiResponse::Line(line.clone()))
+            .is_ok()
+    });
+    if done {
+        for reply in listeners.remove(&chat).unwrap_or_default() {
+            let _ = reply.unbounded_send(ApiResponse::End);
+        }
+    }
+}
+
+fn focus_prompt_input() -> Task<Message> {
+    operate(FocusWithin {
+        target: WidgetId::new(PROMPT_INPUT),
+        inside: false,
+    })
+}
+
+/// Name and color fields for a theme, with a swatch for each valid color.
+fn theme_editor_view(editor: &ThemeFile) -> Element<'_, Message> {
+    let colors = ThemeColor::ALL.into_iter().map(|color| {
+        let value = editor.color(color);
+        let swatch = Color::parse(value.trim()).map(|fill| {
+            container(text(""))
+                .width(Length::Fixed(24.0))
+                .height(Length::Fixed(24.0))
+                .style(move |_: &iced::Theme| container::Style {
+                    background: Some(fill.into()),
+                    border: Border {
+                        color: Color::from_rgb(0.5, 0.5, 0.5),
+                        width: 1.0,
+                        radius: 4.0.into(),
+                    },
+                    ..container::Style::default()
+                })
+        });
+        row![
+            text(color.to_string()).width(Length::Fixed(100.0)),
+            text_input(&tr("theme-color-placeholder"), value)
+                .on_input(move |value| Message::ThemeEditorColor(color, value))
+                .padding(5)
+                .width(Length::Fixed(100.0)),
+        ]
+        .push_maybe(swatch)
+        .spacing(10)
+        .align_y(Alignment::Center)
+        .into()
+    });
+    let error = editor.to_theme().err();
+    column![row![
+        text(tr("theme-name")).width(Length::Fixed(100.0)),
+        text_input(&tr("theme-name-placeholder"), &editor.name)
+            .on_input(Message::ThemeEditorName)
+            .padding(5)
+            .width(Length::Fixed(220.0)),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center)]
+    .extend(colors)
+    .push_maybe(error.map(|e| text(e).size(12).style(text::danger)))
+    .push(
+        row![
+            button(text(tr("theme-save"))).on_press(Message::SaveTheme),
+            button(text(tr("theme-cancel"))).on_press(Message::CancelThemeEdit),
+        ]
+        .spacing(10),
+    )
+    .spacing(5)
+    .padding(10)
+    .into()
+}
+
+/// A reply with its fenced code blocks set in `monospace`.
+fn response_view<'a>(reply: String, monospace: iced::Font) -> Element<'a, Message> {
+    let mut blocks = column![].spacing(5).width(Length::Fill);
+    for (i, part) in reply.split("```").enumerate() {
+        if i % 2 == 0 {
+            let prose = part.trim_matches('\n');
+            if !prose.is_empty() {
+                blocks = blocks.push(text(prose.to_string()).width(Length::Fill));
+            }
+        } else {
+            // The rest of the opening fence's line names the language.
+            let code = part.split_once('\n').map_or(part, |(_, code)| code);
+            blocks = blocks.push(
+                container(text(code.trim_end().to_string()).font(monospace))
+                    .padding(8)
+                    .width(Length::Fill)
+                    .style(container::rounded_box),
+            );
+        }
+    }
+    blocks.into()
+}
+
+fn prompt_form_view(form: &PromptForm) -> Element<'_, Message> {
+    let fields = form
+        .values
+        .iter()
+        .enumerate()
+        .map(|(index, (name, value))| {
+            row![
+                text(name).width(Length::Fixed(120.0)),
+                text_input(name, value)
+                    .on_input(move |v| Message::PromptFormChanged(index, v))
+                    .padding(5)
+                    .width(Length::Fill),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center)
+            .into()
+        });
+    container(
+        column![text(&form.template.name).size(16)]
+            .extend(fields)
+            .push(
+                row![
+                    button(text(tr("prompt-form-send"))).on_press(Message::SubmitPromptForm(true)),
+                    button(text(tr("prompt-form-insert")))
+                        .on_press(Message::SubmitPromptForm(false)),
+                    button(text(tr("prompt-form-cancel"))).on_press(Message::CancelPromptForm),
+                ]
+                .spacing(10),
+            )
+            .spacing(8),
+    )
+    .padding(10)
+    .style(container::rounded_box)
+    .into()
+}
+
+/// First line of a generated title without quotes, markdown or a "Title:" prefix.
+fn clean_title(title: &str) -> Option<String> {
+    let line = title.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let line = line.strip_prefix("Title:").unwrap_or(line);
+    let line = line
+        .trim()
+        .trim_matches(|c: char| matches!(c, '"' | '\'' | '*' | '#' | '`'))
+        .trim_end_matches('.')
+        .trim();
+    let title: String = line.chars().take(60).collect();
+    (!title.is_empty()).then_some(title)
+}
+
 fn borderless_input_style(
 ) -> impl Fn(&iced::Theme, iced::widget::text_input::Status) -> iced::widget::text_input::Style {
     |theme, _status| iced::widget::text_input::Style {
@@ -814,159 +5739,330 @@ fn borderless_input_style(
     }
 }
 
-fn subscribe_to_stream<I: 'static + Hash + Copy + Send + Sync, T: ToString>(
+fn subscribe_to_stream<I: 'static + Hash + Copy + Send + Sync>(
     id: I,
-    url: T,
-    prompt: &str,
-    model: &str,
-    context: Option<Vec<u64>>,
+    backend: &dyn ChatBackend,
+    client: reqwest::Client,
+    base_url: &str,
+    request: ChatRequest,
 ) -> Subscription<(I, Result<OllamaStreamProgress, Error>)> {
     Subscription::run_with_id(
         id,
-        fetch_and_stream_response(
-            url.to_string(),
-            prompt.to_string(),
-            model.to_string(),
-            context,
-        )
-        .map(move |progress| (id, progress)),
+        backend
+            .stream_chat(client, base_url, request)
+            .map(move |progress| (id, progress)),
     )
 }
 
-fn fetch_and_stream_response(
-    url: String,
-    prompt: String,
+/// Polls the server forever: every 10 seconds while it answers, backing off
+/// exponentially while it does not. A new `fingerprint`, the backend and the
+/// connection settings, starts over with the new ones.
+fn subscribe_to_health(
+    backend: &'static dyn ChatBackend,
+    client: reqwest::Client,
+    base_url: &str,
+    server: Uuid,
+    fingerprint: (BackendKind, u64),
+) -> Subscription<(Uuid, Result<String, Error>)> {
+    let base_url = base_url.to_string();
+    Subscription::run_with_id(
+        (server, base_url.clone(), fingerprint, "health"),
+        iced::stream::channel(1, move |mut output| async move {
+            let mut failures = 0;
+            loop {
+                let result = backend.version(client.clone(), &base_url).await;
+                let delay = match result {
+                    Ok(_) => {
+                        failures = 0;
+                        Duration::from_secs(10)
+                    }
+                    Err(_) => {
+                        failures += 1;
+                        retry_delay(failures)
+                    }
+                };
+                if output.send((server, result)).await.is_err() {
+                    break;
+                }
+                tokio::time::sleep(delay).await;
+            }
+        }),
+    )
+}
+
+/// Checks the unsaved profile once, including its auth and TLS settings.
+fn test_connection(id: Uuid, server: &ServerProfile) -> Subscription<Message> {
+    let result = server
+        .client()
+        .map(|client| server.backend().version(client, &server.url));
+    Subscription::run_with_id(
+        id,
+        futures::stream::once(async move {
+            let result = match result {
+                Ok(version) => version.await,
+                Err(e) => Err(Error::Ollama(e)),
+            };
+            Message::ConnectionTested(id, result)
+        }),
+    )
+}
+
+/// Runs a single non-streaming request and yields the full response.
+fn subscribe_to_completion<I: 'static + Hash + Copy + Send + Sync>(
+    id: I,
+    backend: &dyn ChatBackend,
+    client: reqwest::Client,
+    base_url: &str,
     model: String,
-    context: Option<Vec<u64>>,
-) -> impl Stream<Item = Result<OllamaStreamProgress, Error>> {
-    try_channel(1, move |mut output| async move {
-        let client = reqwest::Client::new();
-        let mut body = json!({
-            "model": model,
-            "prompt": prompt,
-            "stream": true
-        });
+    prompt: String,
+) -> Subscription<(I, Result<String, Error>)> {
+    Subscription::run_with_id(
+        id,
+        futures::stream::once(backend.complete(client, base_url, model, prompt))
+            .map(move |result| (id, result)),
+    )
+}
+
+/// Fails a request at once when the server's auth or TLS settings cannot make a client,
+/// instead of sending it without them.
+fn subscribe_to_client_error<I: 'static + Hash + Copy + Send + Sync, T: 'static + Send>(
+    id: I,
+    error: String,
+) -> Subscription<(I, Result<T, Error>)> {
+    Subscription::run_with_id(
+        (id, "client_error"),
+        futures::stream::once(async move { (id, Err(Error::Ollama(error))) }),
+    )
+}
+
+/// Makes a model manager request once and reports its answer.
+fn subscribe_to_model_call(
+    id: Uuid,
+    client: reqwest::Client,
+    server: &ServerProfile,
+    call: ModelCall,
+) -> Subscription<Message> {
+    let request = model_request(client, server, call);
+    Subscription::run_with_id(
+        (id, "model_call"),
+        futures::stream::once(request).map(move |result| Message::ModelCallFinished(id, result)),
+    )
+}
 
-        if let Some(context) = context {
-            body["context"] = json!(context);
+fn model_request(
+    client: reqwest::Client,
+    server: &ServerProfile,
+    call: ModelCall,
+) -> BoxFuture<'static, Result<ModelAnswer, Error>> {
+    let endpoint = |path: &str| server.endpoint(path);
+    match call {
+        ModelCall::List => server
+            .backend()
+            .list_models(client, &server.url)
+            .map(|result| result.map(ModelAnswer::Models))
+            .boxed(),
+        ModelCall::Show(name) | ModelCall::Edit(name) => {
+            let url = endpoint("/api/show");
+            traced(http_span("POST", &url), async move {
+                let show = client
+                    .post(&url)
+                    .json(&json!({ "model": name }))
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<ModelShow>()
+                    .await?;
+                Ok(ModelAnswer::Show(show))
+            })
+            .boxed()
         }
+        ModelCall::Delete(name) => {
+            let url = endpoint("/api/delete");
+            traced(http_span("DELETE", &url), async move {
+                client
+                    .delete(&url)
+                    .json(&json!({ "model": name }))
+                    .send()
+                    .await?
+                    .error_for_status()?;
+                Ok(ModelAnswer::Done)
+            })
+            .boxed()
+        }
+        ModelCall::Copy {
+            source,
+            destination,
+            remove_source,
+        } => {
+            let copy_url = endpoint("/api/copy");
+            let delete_url = endpoint("/api/delete");
+            traced(http_span("POST", &copy_url), async move {
+                client
+                    .post(&copy_url)
+                    .json(&json!({ "source": source, "destination": destination }))
+                    .send()
+                    .await?
+                    .error_for_status()?;
+                if remove_source {
+                    client
+                        .delete(&delete_url)
+                        .json(&json!({ "model": source }))
+                        .send()
+                        .await?
+                        .error_for_status()?;
+                }
+                Ok(ModelAnswer::Done)
+            })
+            .boxed()
+        }
+        ModelCall::Running => {
+            let url = endpoint("/api/ps");
+            traced(http_span("GET", &url), async move {
+                let running = client
+                    .get(&url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json::<RunningModels>()
+                    .await?;
+                Ok(ModelAnswer::Running(running.models))
+            })
+            .boxed()
+        }
+        // Ollama evicts a model from memory when sent an empty request with `keep_alive: 0`.
+        ModelCall::Unload(name) => {
+            let url = endpoint("/api/generate");
+            traced(http_span("POST", &url), async move {
+                client
+                    .post(&url)
+                    .json(&json!({ "model": name, "keep_alive": 0 }))
+                    .send()
+                    .await?
+                    .error_for_status()?;
+                Ok(ModelAnswer::Done)
+            })
+            .boxed()
+        }
+    }
+}
+
+/// Looks up the context window of `model`, for backends that report one.
+fn subscribe_to_context_length(
+    backend: &dyn ChatBackend,
+    client: reqwest::Client,
+    base_url: &str,
+    server: Uuid,
+    model: String,
+    attempt: u32,
+) -> Subscription<(ModelKey, Result<Option<u64>, Error>)> {
+    let key = (server, model.clone());
+    let lookup = backend.context_length(client, base_url, model.clone());
+    Subscription::run_with_id(
+        (server, model, attempt, "context_length"),
+        futures::stream::once(async move {
+            if attempt > 0 {
+                tokio::time::sleep(retry_delay(attempt)).await;
+            }
+            lookup.await
+        })
+        .map(move |result| (key.clone(), result)),
+    )
+}
 
-        let response = client.post(&url).json(&body).send().await?;
+/// Posts `body` to `url` and reports every `status` line of the streamed reply
+/// until Ollama answers with `success`.
+fn subscribe_to_status_stream<I: 'static + Hash + Copy + Send + Sync>(
+    id: I,
+    client: reqwest::Client,
+    url: String,
+    body: serde_json::Value,
+) -> Subscription<(I, Result<DownloadProgressUpdate, Error>)> {
+    Subscription::run_with_id(
+        id,
+        stream_status(client, url, body).map(move |progress| (id, progress)),
+    )
+}
 
-        let mut stream = response.bytes_stream();
-        while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
-            let chunk_str = String::from_utf8_lossy(&chunk).to_string();
+pub(crate) fn stream_status(
+    client: reqwest::Client,
+    url: String,
+    body: serde_json::Value,
+) -> impl Stream<Item = Result<DownloadProgressUpdate, Error>> {
+    let span = http_span("POST", &url);
+    try_channel(1, move |mut output| {
+        traced(span, async move {
+            let response = client.post(&url).json(&body).send().await?;
+            let mut stream = response.bytes_stream();
+            let mut buffer = String::new();
 
-            if let Ok(json_value) = serde_json::from_str::<serde_json::Value>(&chunk_str) {
-                if json_value
-                    .get("done")
-                    .and_then(|v| v.as_bool())
-                    .unwrap_or(false)
-                {
-                    let context = json_value
-                        .get("context")
-                        .and_then(|v| v.as_array())
-                        .map(|arr| arr.iter().filter_map(|x| x.as_u64()).collect::<Vec<u64>>())
-                        .unwrap_or_else(Vec::new);
-                    let _ = output
-                        .send(OllamaStreamProgress::Finished { context })
-                        .await;
-                    break;
-                } else {
-                    let token = json_value
-                        .get("response")
+            while let Some(chunk) = stream.next().await {
+                buffer.push_str(&String::from_utf8_lossy(&chunk?));
+                while let Some(newline) = buffer.find('\n') {
+                    let line: String = buffer.drain(..=newline).collect();
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let json = serde_json::from_str::<serde_json::Value>(line.trim())
+                        .map_err(|e| Error::ParseError(Arc::new(e)))?;
+                    if let Some(error) = json.get("error").and_then(|v| v.as_str()) {
+                        return Err(Error::Ollama(error.to_string()));
+                    }
+                    let status = json
+                        .get("status")
                         .and_then(|v| v.as_str())
-                        .unwrap_or(&chunk_str)
+                        .unwrap_or_default()
                         .to_string();
-                    let _ = output.send(OllamaStreamProgress::Streaming { token }).await;
+                    if status == "success" {
+                        output.send(DownloadProgressUpdate::Finished).await?;
+                        return Ok(());
+                    }
+                    output
+                        .send(DownloadProgressUpdate::Progress {
+                            status,
+                            digest: json
+                                .get("digest")
+                                .and_then(|v| v.as_str())
+                                .unwrap_or_default()
+                                .to_string(),
+                            total: json.get("total").and_then(|v| v.as_u64()).unwrap_or(0),
+                            completed: json.get("completed").and_then(|v| v.as_u64()).unwrap_or(0),
+                        })
+                        .await?;
                 }
-            } else {
-                let _ = output
-                    .send(OllamaStreamProgress::Streaming { token: chunk_str })
-                    .await;
             }
-        }
-        Ok(())
+            Err(Error::Ollama(
+                "connection closed before the request finished".to_string(),
+            ))
+        })
     })
 }
 
-fn subscribe_to_download<I: 'static + Hash + Send + Sync + Clone>(
-    id: I,
+/// Pulls `model`. Retries wait with exponential backoff first; Ollama keeps the
+/// partially downloaded blobs, so a retry resumes where the last attempt stopped.
+fn subscribe_to_download(
+    id: Uuid,
+    attempt: u32,
+    client: reqwest::Client,
     url: String,
     model: String,
-) -> Subscription<(I, Result<DownloadProgressUpdate, Error>)> {
+) -> Subscription<(Uuid, Result<DownloadProgressUpdate, Error>)> {
     Subscription::run_with_id(
-        id.clone(),
-        iced::futures::stream::unfold(
-            (id, url, model, None),
-            move |(id, url, model, client)| async move {
-                let client = client.unwrap_or_else(|| reqwest::Client::new());
-                let body = json!({ "model": model, "stream": true });
-
-                match client.post(&url).json(&body).send().await {
-                    Ok(response) => {
-                        let mut stream = response.bytes_stream();
-                        while let Some(chunk) = stream.next().await {
-                            match chunk {
-                                Ok(bytes) => {
-                                    let chunk_str = String::from_utf8_lossy(&bytes);
-                                    for line in chunk_str.lines() {
-                                        match serde_json::from_str::<serde_json::Value>(line) {
-                                            Ok(json) => {
-                                                if let Some(status) =
-                                                    json.get("status").and_then(|v| v.as_str())
-                                                {
-                                                    let total = json
-                                                        .get("total")
-                                                        .and_then(|v| v.as_u64())
-                                                        .unwrap_or(0);
-                                                    let completed = json
-                                                        .get("completed")
-                                                        .and_then(|v| v.as_u64())
-                                                        .unwrap_or(0);
-
-                                                    return Some((
-                                                        (
-                                                            id.clone(),
-                                                            Ok(DownloadProgressUpdate::Progress {
-                                                                status: status.to_string(),
-                                                                total,
-                                                                completed,
-                                                            }),
-                                                        ),
-                                                        (id, url, model, Some(client)),
-                                                    ));
-                                                }
-                                            }
-                                            Err(e) => {
-                                                return Some((
-                                                    (
-                                                        id.clone(),
-                                                        Err(Error::ParseError(Arc::new(e))),
-                                                    ),
-                                                    (id, url, model, Some(client)),
-                                                ))
-                                            }
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    return Some((
-                                        (id.clone(), Err(Error::RequestFailed(Arc::new(e)))),
-                                        (id, url, model, Some(client)),
-                                    ))
-                                }
-                            }
-                        }
-                        None
-                    }
-                    Err(e) => Some((
-                        (id.clone(), Err(Error::RequestFailed(Arc::new(e)))),
-                        (id, url, model, Some(client)),
-                    )),
-                }
-            },
-        ),
+        (id, attempt),
+        futures::stream::once(async move {
+            if attempt > 0 {
+                tokio::time::sleep(retry_delay(attempt)).await;
+            }
+        })
+        .flat_map(move |_| {
+            stream_status(
+                client.clone(),
+                url.clone(),
+                json!({ "model": model, "stream": true }),
+            )
+        })
+        .map(move |progress| (id, progress)),
     )
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,476 @@
+//! Drives `OllamaGUI::update` the way the runtime would, against a fake Ollama
+//! server and inside a temporary data directory.
+
+mod fake_ollama;
+
+use super::*;
+use fake_ollama::{FakeOllama, FakeResponse};
+use serde_json::Value;
+use std::sync::{Mutex, MutexGuard};
+
+/// The app reads and writes relative to the working directory, which is shared
+/// by every test thread, so tests that use it run one at a time.
+static WORKING_DIR: Mutex<()> = Mutex::new(());
+
+/// An empty working directory whose settings point at `server`. Restores the
+/// previous directory and deletes this one when dropped.
+struct DataDir {
+    path: PathBuf,
+    previous: PathBuf,
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl DataDir {
+    fn new(server: &FakeOllama) -> Self {
+        let lock = WORKING_DIR.lock().unwrap_or_else(|e| e.into_inner());
+        let path = std::env::temp_dir().join(format!("rusty-ollama-gui-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(path.join("settings")).unwrap();
+        fs::write(
+            path.join("settings/settings.json"),
+            json!({
+                "theme": "Dark",
+                "default_url": server.url(),
+                "selected_model": "fake-model",
+            })
+            .to_string(),
+        )
+        .unwrap();
+        let previous = std::env::current_dir().unwrap();
+        std::env::set_current_dir(&path).unwrap();
+        Self {
+            path,
+            previous,
+            _lock: lock,
+        }
+    }
+}
+
+impl Drop for DataDir {
+    fn drop(&mut self) {
+        let _ = std::env::set_current_dir(&self.previous);
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+fn send(gui: &mut OllamaGUI, message: Message) {
+    let _ = gui.update(message);
+}
+
+fn chat(gui: &OllamaGUI, id: Uuid) -> &OllamaChat {
+    gui.chats.iter().find(|c| c.uuid == id).unwrap()
+}
+
+fn type_prompt(gui: &mut OllamaGUI, id: Uuid, prompt: &str) {
+    send(
+        gui,
+        Message::PromptAction(
+            id,
+            text_editor::Action::Edit(text_editor::Edit::Paste(Arc::new(prompt.to_string()))),
+        ),
+    );
+}
+
+/// Runs the request the chat's subscription would make, feeding the first
+/// `limit` updates back as `ChatProgress` messages.
+fn stream_reply(gui: &mut OllamaGUI, runtime: &tokio::runtime::Runtime, id: Uuid, limit: usize) {
+    let chat = chat(gui, id);
+    let server = gui.server_by_id(chat.server);
+    let stream = server.backend().stream_chat(
+        gui.client(chat.server).unwrap(),
+        &server.url,
+        chat.chat_request(),
+    );
+    let updates: Vec<_> = runtime.block_on(stream.take(limit).collect());
+    for update in updates {
+        send(gui, Message::ChatProgress((id, update)));
+    }
+}
+
+/// Makes the model manager requests the subscriptions would, feeding the
+/// answers back as `ModelCallFinished` messages.
+fn finish_model_calls(gui: &mut OllamaGUI, runtime: &tokio::runtime::Runtime) {
+    for pending in gui.model_calls.clone() {
+        let server = gui.server_by_id(pending.server);
+        let request = model_request(gui.client(pending.server).unwrap(), server, pending.call);
+        let result = runtime.block_on(request);
+        send(gui, Message::ModelCallFinished(pending.id, result));
+    }
+}
+
+fn saved_chat(id: Uuid) -> ChatHistory {
+    let contents = fs::read_to_string(format!("./chats/{}.json", id)).unwrap();
+    serde_json::from_str(&contents).unwrap()
+}
+
+fn generate_reply(tokens: &[&str]) -> FakeResponse {
+    let mut lines: Vec<Value> = tokens
+        .iter()
+        .map(|token| json!({ "model": "fake-model", "response": token, "done": false }))
+        .collect();
+    lines.push(json!({
+        "model": "fake-model",
+        "response": "",
+        "done": true,
+        "context": [1, 2, 3],
+        "total_duration": 3000,
+        "eval_count": tokens.len(),
+        "eval_duration": 2000,
+    }));
+    FakeResponse::ndjson(&lines)
+}
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Runtime::new().unwrap()
+}
+
+#[test]
+fn new_chat_streams_a_split_reply_and_saves_it() {
+    let server = FakeOllama::start();
+    // 7-byte chunks split the JSON lines and the two-byte "ö".
+    server.route(
+        "POST",
+        "/api/generate",
+        generate_reply(&["Hel", "lo, ", "wörld"]).split(7),
+    );
+    let _dir = DataDir::new(&server);
+    let runtime = runtime();
+    let mut gui = OllamaGUI::new();
+
+    send(&mut gui, Message::NewChat);
+    let id = gui.current_chat;
+    assert_eq!(gui.chats.len(), 2);
+    type_prompt(&mut gui, id, "Hi");
+    send(&mut gui, Message::StartChat(id));
+    assert!(matches!(chat(&gui, id).state, ChatState::Streaming));
+    assert_eq!(chat(&gui, id).input_text(), "");
+
+    stream_reply(&mut gui, &runtime, id, usize::MAX);
+
+    let chat = chat(&gui, id);
+    assert!(matches!(chat.state, ChatState::Finished));
+    assert_eq!(chat.chat_entries[0].response, "Hello, wörld");
+    assert_eq!(chat.context, Some(vec![1, 2, 3]));
+    assert_eq!(chat.chat_entries[0].stats.as_ref().unwrap().eval_count, 3);
+
+    let request = &server.requests("/api/generate")[0];
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.body["model"], "fake-model");
+    assert_eq!(request.body["prompt"], "Hi");
+
+    let saved = saved_chat(id);
+    assert_eq!(saved.chat[0].prompt, "Hi");
+    assert_eq!(saved.chat[0].response, "Hello, wörld");
+    assert_eq!(saved.chat[0].model.as_deref(), Some("fake-model"));
+    assert_eq!(saved.context, vec![1, 2, 3]);
+}
+
+#[test]
+fn follow_up_prompt_sends_the_returned_context() {
+    let server = FakeOllama::start();
+    server.route("POST", "/api/generate", generate_reply(&["One"]));
+    let _dir = DataDir::new(&server);
+    let runtime = runtime();
+    let mut gui = OllamaGUI::new();
+    let id = gui.current_chat;
+
+    type_prompt(&mut gui, id, "First");
+    send(&mut gui, Message::StartChat(id));
+    stream_reply(&mut gui, &runtime, id, usize::MAX);
+    type_prompt(&mut gui, id, "Second");
+    send(&mut gui, Message::StartChat(id));
+    stream_reply(&mut gui, &runtime, id, usize::MAX);
+
+    let requests = server.requests("/api/generate");
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].body["prompt"], "Second");
+    assert_eq!(requests[1].body["context"], json!([1, 2, 3]));
+    assert_eq!(saved_chat(id).chat.len(), 2);
+}
+
+#[test]
+fn error_response_marks_the_chat_errored() {
+    let server = FakeOllama::start();
+    server.route(
+        "POST",
+        "/api/generate",
+        FakeResponse::error(404, "model \"fake-model\" not found"),
+    );
+    let _dir = DataDir::new(&server);
+    let runtime = runtime();
+    let mut gui = OllamaGUI::new();
+    let id = gui.current_chat;
+
+    type_prompt(&mut gui, id, "Hi");
+    send(&mut gui, Message::StartChat(id));
+    let chat_request = chat(&gui, id).chat_request();
+    let server_url = gui.server().url.clone();
+    let result: Vec<_> = runtime.block_on(
+        gui.server()
+            .backend()
+            .stream_chat(
+                gui.client(gui.active_server).unwrap(),
+                &server_url,
+                chat_request,
+            )
+            .collect(),
+    );
+    match &result[..] {
+        [Err(Error::Ollama(message))] => assert!(message.contains("not found"), "{}", message),
+        other => panic!("expected one error, got {:?}", other),
+    }
+    for update in result {
+        send(&mut gui, Message::ChatProgress((id, update)));
+    }
+
+    assert!(matches!(chat(&gui, id).state, ChatState::Errored));
+    assert!(chat(&gui, id)
+        .error
+        .as_ref()
+        .is_some_and(|error| error.contains("model \"fake-model\" not found")));
+    assert_eq!(chat(&gui, id).chat_entries[0].response, "");
+    assert!(!PathBuf::from(format!("./chats/{}.json", id)).exists());
+}
+
+#[test]
+fn stopping_keeps_the_partial_reply() {
+    let server = FakeOllama::start();
+    server.route(
+        "POST",
+        "/api/generate",
+        generate_reply(&["Once ", "upon ", "a time"]).delay(Duration::from_millis(20)),
+    );
+    let _dir = DataDir::new(&server);
+    let runtime = runtime();
+    let mut gui = OllamaGUI::new();
+    let id = gui.current_chat;
+
+    type_prompt(&mut gui, id, "Tell a story");
+    send(&mut gui, Message::StartChat(id));
+    stream_reply(&mut gui, &runtime, id, 2);
+    send(&mut gui, Message::StopChat(id));
+
+    let chat = chat(&gui, id);
+    assert!(matches!(chat.state, ChatState::Finished));
+    assert_eq!(chat.chat_entries[0].response, "Once upon ");
+    assert_eq!(chat.context, None);
+    assert_eq!(saved_chat(id).chat[0].response, "Once upon ");
+}
+
+#[test]
+fn rename_and_delete_update_the_chat_files() {
+    let server = FakeOllama::start();
+    server.route("POST", "/api/generate", generate_reply(&["Hi!"]));
+    let _dir = DataDir::new(&server);
+    let runtime = runtime();
+    let mut gui = OllamaGUI::new();
+    let first = gui.current_chat;
+    send(&mut gui, Message::NewChat);
+    let id = gui.current_chat;
+    type_prompt(&mut gui, id, "Hello");
+    send(&mut gui, Message::StartChat(id));
+    stream_reply(&mut gui, &runtime, id, usize::MAX);
+
+    send(&mut gui, Message::StartRenameChat(id));
+    assert_eq!(gui.editing_chat, Some(id));
+    send(
+        &mut gui,
+        Message::UpdateTempName(id, "  Greetings ".to_string()),
+    );
+    send(&mut gui, Message::FinishRenameChat(id));
+    assert_eq!(gui.editing_chat, None);
+    assert_eq!(chat(&gui, id).display_name, "Greetings");
+    let saved = saved_chat(id);
+    assert_eq!(saved.display_name, "Greetings");
+    assert_eq!(saved.renamed, Some(true));
+
+    send(&mut gui, Message::DeleteChat(id));
+    assert!(gui.chats.iter().all(|c| c.uuid != id));
+    assert_eq!(gui.current_chat, first);
+    assert!(!PathBuf::from(format!("./chats/{}.json", id)).exists());
+}
+
+#[test]
+fn saving_keeps_turns_another_process_added() {
+    let server = FakeOllama::start();
+    server.route("POST", "/api/generate", generate_reply(&["Hi!"]));
+    let _dir = DataDir::new(&server);
+    let runtime = runtime();
+    let mut gui = OllamaGUI::new();
+    let id = gui.current_chat;
+    type_prompt(&mut gui, id, "Hello");
+    send(&mut gui, Message::StartChat(id));
+    stream_reply(&mut gui, &runtime, id, usize::MAX);
+
+    // What `cli chat` does: load the chat, add a turn and save it.
+    let mut other = OllamaChat::load_all()
+        .into_iter()
+        .find(|c| c.uuid == id)
+        .unwrap();
+    other.chat_entries.push(ChatEntry {
+        prompt: "From the terminal".to_string(),
+        response: "Noted".to_string(),
+        stats: None,
+        model: None,
+    });
+    other.save_chat_history();
+
+    type_prompt(&mut gui, id, "Unsent");
+    send(&mut gui, Message::SaveDrafts);
+    let saved = saved_chat(id);
+    let prompts: Vec<_> = saved.chat.iter().map(|e| e.prompt.as_str()).collect();
+    assert_eq!(prompts, ["Hello", "From the terminal"]);
+    assert_eq!(saved.draft, "Unsent");
+    assert_eq!(chat(&gui, id).chat_entries.len(), 2);
+    assert!(!PathBuf::from(format!("./chats/{}.json.tmp", id)).exists());
+}
+
+#[test]
+fn chats_saved_before_titles_count_as_renamed_unless_unnamed() {
+    let server = FakeOllama::start();
+    let _dir = DataDir::new(&server);
+    fs::create_dir_all("./chats").unwrap();
+    let mut ids = Vec::new();
+    for name in [DEFAULT_CHAT_NAME, "Trip plans"] {
+        let id = Uuid::new_v4();
+        fs::write(
+            format!("./chats/{}.json", id),
+            json!({
+                "display_name": name,
+                "uuid": id.to_string(),
+                "context": [],
+                "model": "fake-model",
+                "chat": [{ "prompt": "Hi", "response": "Hello" }],
+            })
+            .to_string(),
+        )
+        .unwrap();
+        ids.push(id);
+    }
+
+    let chats = OllamaChat::load_all();
+    let renamed = |id| chats.iter().find(|c| c.uuid == id).unwrap().renamed;
+    assert!(!renamed(ids[0]));
+    assert!(renamed(ids[1]));
+}
+
+#[test]
+fn pull_progress_is_tracked_until_the_model_is_installed() {
+    let server = FakeOllama::start();
+    server.route(
+        "POST",
+        "/api/pull",
+        FakeResponse::ndjson(&[
+            json!({ "status": "pulling manifest" }),
+            json!({ "status": "pulling abc", "digest": "sha256:abc", "total": 100, "completed": 40 }),
+            json!({ "status": "pulling abc", "digest": "sha256:abc", "total": 100, "completed": 100 }),
+            json!({ "status": "success" }),
+        ])
+        .split(16),
+    );
+    let _dir = DataDir::new(&server);
+    let runtime = runtime();
+    let mut gui = OllamaGUI::new();
+
+    send(
+        &mut gui,
+        Message::DownloadModelInputChanged("tiny".to_string()),
+    );
+    send(&mut gui, Message::StartDownloadModel);
+    assert_eq!(gui.downloads.len(), 1);
+    assert_eq!(gui.downloads[0].state, DownloadState::Active);
+    assert!(fs::read_to_string("./settings/downloads.json")
+        .unwrap()
+        .contains("\"tiny\""));
+
+    // Once the pull succeeds the model list is reloaded and includes it.
+    server.route(
+        "GET",
+        "/api/tags",
+        FakeResponse::json(json!({ "models": [{
+            "name": "tiny",
+            "modified_at": "2024-01-01T00:00:00Z",
+            "size": 100,
+            "digest": "abc",
+            "details": {
+                "format": "gguf",
+                "family": "llama",
+                "parameter_size": "1B",
+                "quantization_level": "Q4_0",
+            },
+        }] })),
+    );
+
+    let id = gui.downloads[0].progress.id;
+    let url = gui.downloads[0].url.clone();
+    let updates: Vec<_> = runtime.block_on(
+        stream_status(
+            gui.client(gui.active_server).unwrap(),
+            url,
+            json!({ "model": "tiny", "stream": true }),
+        )
+        .collect(),
+    );
+    assert_eq!(updates.len(), 4);
+    let mut updates = updates.into_iter();
+    for update in updates.by_ref().take(3) {
+        send(&mut gui, Message::DownloadProgress(id, update));
+    }
+    assert_eq!(gui.downloads[0].progress.status, "pulling abc");
+    assert_eq!(gui.downloads[0].progress.total, 100);
+    assert_eq!(gui.downloads[0].progress.completed, 100);
+
+    send(
+        &mut gui,
+        Message::DownloadProgress(id, updates.next().unwrap()),
+    );
+    assert!(gui.downloads.is_empty());
+    assert_eq!(gui.notice.as_deref(), Some("Downloaded tiny"));
+    finish_model_calls(&mut gui, &runtime);
+    assert_eq!(gui.model_names(), vec!["tiny".to_string()]);
+    assert_eq!(server.requests("/api/pull")[0].body["model"], "tiny");
+    assert!(!fs::read_to_string("./settings/downloads.json")
+        .unwrap()
+        .contains("\"tiny\""));
+}
+
+#[test]
+fn cancelled_download_is_forgotten() {
+    let server = FakeOllama::start();
+    let _dir = DataDir::new(&server);
+    let mut gui = OllamaGUI::new();
+
+    send(
+        &mut gui,
+        Message::DownloadModelInputChanged("big".to_string()),
+    );
+    send(&mut gui, Message::StartDownloadModel);
+    let id = gui.downloads[0].progress.id;
+    send(&mut gui, Message::CancelDownload(id));
+
+    assert!(gui.downloads.is_empty());
+    assert!(!fs::read_to_string("./settings/downloads.json")
+        .unwrap()
+        .contains("\"big\""));
+}
+
+#[test]
+fn launch_options_win_over_settings_without_being_saved() {
+    let server = FakeOllama::start();
+    let _dir = DataDir::new(&server);
+    let options = LaunchOptions {
+        server_url: Some("http://127.0.0.1:9".to_string()),
+        model: Some("flag-model".to_string()),
+        ..LaunchOptions::default()
+    };
+    let gui = OllamaGUI::with_options(&options);
+    assert_eq!(gui.selected_model, "flag-model");
+    assert_eq!(gui.server().url, "http://127.0.0.1:9");
+    assert_eq!(gui.servers.len(), 2);
+
+    gui.save_settings();
+    let saved = OllamaGUI::load_settings();
+    assert_eq!(saved.selected_model, "fake-model");
+    assert_eq!(saved.default_url, server.url());
+    assert_eq!(saved.servers.len(), 1);
+    assert_eq!(saved.theme, "Dark");
+}
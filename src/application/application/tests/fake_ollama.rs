@@ -0,0 +1,192 @@
+//! A small HTTP server that answers like Ollama from canned responses.
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// What the server answers on one route. The body is sent with chunked
+/// encoding, one HTTP chunk per entry in `chunks`.
+#[derive(Debug, Clone)]
+pub struct FakeResponse {
+    status: u16,
+    chunks: Vec<Vec<u8>>,
+    /// Pause before each chunk.
+    delay: Duration,
+}
+
+impl FakeResponse {
+    pub fn json(value: Value) -> Self {
+        Self {
+            status: 200,
+            chunks: vec![value.to_string().into_bytes()],
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// Newline-delimited JSON, one line per chunk, as Ollama streams it.
+    pub fn ndjson(lines: &[Value]) -> Self {
+        Self {
+            status: 200,
+            chunks: lines
+                .iter()
+                .map(|line| format!("{}\n", line).into_bytes())
+                .collect(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// An error status with Ollama's `{"error": ...}` body.
+    pub fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            ..Self::json(serde_json::json!({ "error": message }))
+        }
+    }
+
+    /// Re-chunks the body into pieces of `size` bytes, so lines and even
+    /// characters arrive split across chunks.
+    pub fn split(mut self, size: usize) -> Self {
+        let body = self.chunks.concat();
+        self.chunks = body.chunks(size).map(<[u8]>::to_vec).collect();
+        self
+    }
+
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+/// A request the server received.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub body: Value,
+}
+
+type Routes = Arc<Mutex<HashMap<(String, String), FakeResponse>>>;
+
+/// Listens on a free localhost port until the test process exits.
+pub struct FakeOllama {
+    url: String,
+    routes: Routes,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl FakeOllama {
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let routes: Routes = Arc::default();
+        let requests: Arc<Mutex<Vec<RecordedRequest>>> = Arc::default();
+
+        let (server_routes, server_requests) = (routes.clone(), requests.clone());
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let (routes, requests) = (server_routes.clone(), server_requests.clone());
+                thread::spawn(move || handle(stream, routes, requests));
+            }
+        });
+
+        let server = Self {
+            url,
+            routes,
+            requests,
+        };
+        server.route(
+            "GET",
+            "/api/version",
+            FakeResponse::json(serde_json::json!({ "version": "0.0.0-fake" })),
+        );
+        server.route(
+            "GET",
+            "/api/tags",
+            FakeResponse::json(serde_json::json!({ "models": [] })),
+        );
+        server
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Answers `method path` with `response` from now on.
+    pub fn route(&self, method: &str, path: &str, response: FakeResponse) {
+        self.routes
+            .lock()
+            .unwrap()
+            .insert((method.to_string(), path.to_string()), response);
+    }
+
+    /// Requests received on `path`, oldest first.
+    pub fn requests(&self, path: &str) -> Vec<RecordedRequest> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request.path == path)
+            .cloned()
+            .collect()
+    }
+}
+
+fn handle(
+    mut stream: TcpStream,
+    routes: Routes,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header)?;
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    requests.lock().unwrap().push(RecordedRequest {
+        method: method.clone(),
+        path: path.clone(),
+        body: serde_json::from_slice(&body).unwrap_or(Value::Null),
+    });
+
+    let response = routes
+        .lock()
+        .unwrap()
+        .get(&(method, path))
+        .cloned()
+        .unwrap_or_else(|| FakeResponse::error(404, "not found"));
+    write!(
+        stream,
+        "HTTP/1.1 {} Fake\r\nContent-Type: application/x-ndjson\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n",
+        response.status
+    )?;
+    stream.flush()?;
+    for chunk in &response.chunks {
+        thread::sleep(response.delay);
+        write!(stream, "{:x}\r\n", chunk.len())?;
+        stream.write_all(chunk)?;
+        stream.write_all(b"\r\n")?;
+        stream.flush()?;
+    }
+    stream.write_all(b"0\r\n\r\n")?;
+    stream.flush()
+}
@@ -0,0 +1,548 @@
+use crate::application::application::{
+    Error, GenerationStats, ModelDetails, ModelInfo, OllamaStreamProgress,
+};
+use crate::application::diagnostics::diagnostics::{http_span, traced};
+use crate::application::i18n::i18n::tr;
+use iced::futures::future::BoxFuture;
+use iced::futures::stream::BoxStream;
+use iced::futures::{FutureExt, SinkExt, StreamExt};
+use iced::stream::try_channel;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::Instrument;
+
+/// The protocol a server speaks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BackendKind {
+    #[default]
+    Ollama,
+    /// `/v1/chat/completions` as served by llama.cpp, vLLM, LM Studio and others.
+    OpenAi,
+}
+
+impl BackendKind {
+    pub const ALL: [BackendKind; 2] = [BackendKind::Ollama, BackendKind::OpenAi];
+
+    pub fn backend(self) -> &'static dyn ChatBackend {
+        match self {
+            BackendKind::Ollama => &OllamaBackend,
+            BackendKind::OpenAi => &OpenAiBackend,
+        }
+    }
+
+    /// Whether the server has Ollama's endpoints for showing, listing running,
+    /// pulling, creating, copying and deleting models.
+    pub fn manages_models(self) -> bool {
+        matches!(self, BackendKind::Ollama)
+    }
+}
+
+impl std::fmt::Display for BackendKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BackendKind::Ollama => write!(f, "Ollama"),
+            BackendKind::OpenAi => write!(f, "{}", tr("backend-openai")),
+        }
+    }
+}
+
+/// Everything a backend needs to answer the last prompt of a chat.
+#[derive(Debug, Clone)]
+pub struct ChatRequest {
+    pub model: String,
+    pub prompt: String,
+    /// Earlier `(prompt, response)` turns still in the context window, oldest first.
+    pub history: Vec<(String, String)>,
+    /// Summary of the turns that were trimmed from `history`.
+    pub summary: Option<String>,
+    /// Token context of the previous Ollama reply. When set it already covers `history`.
+    pub context: Option<Vec<u64>>,
+    pub num_ctx: Option<u64>,
+}
+
+/// A server that can hold a chat. Each method takes the base URL of the server profile
+/// and returns a `'static` future or stream so it can run inside a subscription.
+pub trait ChatBackend: Send + Sync {
+    /// Streams the reply to `request.prompt` token by token.
+    fn stream_chat(
+        &self,
+        client: reqwest::Client,
+        base_url: &str,
+        request: ChatRequest,
+    ) -> BoxStream<'static, Result<OllamaStreamProgress, Error>>;
+
+    /// Answers a single prompt without streaming or history.
+    fn complete(
+        &self,
+        client: reqwest::Client,
+        base_url: &str,
+        model: String,
+        prompt: String,
+    ) -> BoxFuture<'static, Result<String, Error>>;
+
+    fn list_models(
+        &self,
+        client: reqwest::Client,
+        base_url: &str,
+    ) -> BoxFuture<'static, Result<Vec<ModelInfo>, Error>>;
+
+    /// Describes the server for the status badge. Also serves as the health check.
+    fn version(
+        &self,
+        client: reqwest::Client,
+        base_url: &str,
+    ) -> BoxFuture<'static, Result<String, Error>>;
+
+    /// Context window of `model`, if the server reports one.
+    fn context_length(
+        &self,
+        client: reqwest::Client,
+        base_url: &str,
+        model: String,
+    ) -> BoxFuture<'static, Result<Option<u64>, Error>>;
+}
+
+/// Earlier turns as plain text, for backends that cannot be sent the history directly.
+pub fn transcript(summary: Option<&str>, history: &[(String, String)]) -> String {
+    let mut text = String::new();
+    if let Some(summary) = summary {
+        text.push_str(&format!(
+            "Summary of the earlier conversation:\n{}\n\n",
+            summary
+        ));
+    }
+    for (prompt, response) in history {
+        text.push_str(&format!("User: {}\nAssistant: {}\n\n", prompt, response));
+    }
+    text
+}
+
+fn parse_error(e: serde_json::Error) -> Error {
+    Error::ParseError(Arc::new(e))
+}
+
+pub struct OllamaBackend;
+
+impl ChatBackend for OllamaBackend {
+    fn stream_chat(
+        &self,
+        client: reqwest::Client,
+        base_url: &str,
+        request: ChatRequest,
+    ) -> BoxStream<'static, Result<OllamaStreamProgress, Error>> {
+        let url = format!("{}/api/generate", base_url);
+        let span = http_span("POST", &url);
+        try_channel(1, move |mut output| {
+            traced(span, async move {
+                let prompt = match &request.context {
+                    Some(_) => request.prompt.clone(),
+                    None => {
+                        let history = transcript(request.summary.as_deref(), &request.history);
+                        if history.is_empty() {
+                            request.prompt.clone()
+                        } else {
+                            format!("{}User: {}", history, request.prompt)
+                        }
+                    }
+                };
+                let mut body = json!({
+                    "model": request.model,
+                    "prompt": prompt,
+                    "stream": true
+                });
+
+                if let Some(context) = request.context {
+                    body["context"] = json!(context);
+                }
+                if let Some(num_ctx) = request.num_ctx {
+                    body["options"] = json!({ "num_ctx": num_ctx });
+                }
+
+                let response = client.post(&url).json(&body).send().await?;
+                if !response.status().is_success() {
+                    let status = response.status();
+                    let text = response.text().await.unwrap_or_default();
+                    let message = serde_json::from_str::<serde_json::Value>(&text)
+                        .ok()
+                        .and_then(|json| json["error"].as_str().map(str::to_string))
+                        .unwrap_or_else(|| text.trim().to_string());
+                    return Err(Error::Ollama(format!("{}: {}", status, message)));
+                }
+
+                // One JSON object per line. A line may arrive split over several
+                // chunks, even inside a multi-byte character, or several in one chunk.
+                let mut buffer = Vec::new();
+                let mut stream = response.bytes_stream();
+                while let Some(chunk) = stream.next().await {
+                    buffer.extend_from_slice(&chunk?);
+                    while let Some(newline) = buffer.iter().position(|b| *b == b'\n') {
+                        let line: Vec<u8> = buffer.drain(..=newline).collect();
+                        if line.trim_ascii().is_empty() {
+                            continue;
+                        }
+                        let json_value: serde_json::Value =
+                            serde_json::from_slice(&line).map_err(parse_error)?;
+                        if let Some(error) = json_value["error"].as_str() {
+                            return Err(Error::Ollama(error.to_string()));
+                        }
+                        if json_value
+                            .get("done")
+                            .and_then(|v| v.as_bool())
+                            .unwrap_or(false)
+                        {
+                            let context = json_value
+                                .get("context")
+                                .and_then(|v| v.as_array())
+                                .map(|arr| {
+                                    arr.iter().filter_map(|x| x.as_u64()).collect::<Vec<u64>>()
+                                })
+                                .unwrap_or_default();
+                            let stats = serde_json::from_value(json_value).unwrap_or_default();
+                            let _ = output
+                                .send(OllamaStreamProgress::Finished { context, stats })
+                                .await;
+                            return Ok(());
+                        }
+                        let token = json_value
+                            .get("response")
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default()
+                            .to_string();
+                        let _ = output.send(OllamaStreamProgress::Streaming { token }).await;
+                    }
+                }
+                Err(Error::Ollama(
+                    "connection closed before the reply finished".to_string(),
+                ))
+            })
+        })
+        .boxed()
+    }
+
+    fn complete(
+        &self,
+        client: reqwest::Client,
+        base_url: &str,
+        model: String,
+        prompt: String,
+    ) -> BoxFuture<'static, Result<String, Error>> {
+        let url = format!("{}/api/generate", base_url);
+        let span = http_span("POST", &url);
+        traced(span, async move {
+            let body = json!({ "model": model, "prompt": prompt, "stream": false });
+            let response = client
+                .post(&url)
+                .json(&body)
+                .send()
+                .await?
+                .error_for_status()?;
+            let json = response.json::<serde_json::Value>().await?;
+            Ok(json
+                .get("response")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .trim()
+                .to_string())
+        })
+        .boxed()
+    }
+
+    fn list_models(
+        &self,
+        client: reqwest::Client,
+        base_url: &str,
+    ) -> BoxFuture<'static, Result<Vec<ModelInfo>, Error>> {
+        #[derive(Deserialize)]
+        struct Tags {
+            models: Vec<ModelInfo>,
+        }
+        let url = format!("{}/api/tags", base_url);
+        let span = http_span("GET", &url);
+        traced(span, async move {
+            Ok(client
+                .get(&url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<Tags>()
+                .await?
+                .models)
+        })
+        .boxed()
+    }
+
+    fn version(
+        &self,
+        client: reqwest::Client,
+        base_url: &str,
+    ) -> BoxFuture<'static, Result<String, Error>> {
+        let url = format!("{}/api/version", base_url);
+        let span = http_span("GET", &url);
+        async move {
+            let response = client.get(&url).send().await?.error_for_status()?;
+            let version: serde_json::Value = response.json().await?;
+            Ok(format!(
+                "Ollama v{}",
+                version["version"].as_str().unwrap_or("?")
+            ))
+        }
+        .instrument(span)
+        .boxed()
+    }
+
+    fn context_length(
+        &self,
+        client: reqwest::Client,
+        base_url: &str,
+        model: String,
+    ) -> BoxFuture<'static, Result<Option<u64>, Error>> {
+        let url = format!("{}/api/show", base_url);
+        let span = http_span("POST", &url);
+        traced(span, async move {
+            let response = client
+                .post(&url)
+                .json(&json!({ "model": model }))
+                .send()
+                .await?
+                .error_for_status()?;
+            let json = response.json::<serde_json::Value>().await?;
+            Ok(json
+                .get("model_info")
+                .and_then(|v| v.as_object())
+                .and_then(|info| {
+                    info.iter()
+                        .find(|(key, _)| key.ends_with(".context_length"))
+                        .and_then(|(_, v)| v.as_u64())
+                }))
+        })
+        .boxed()
+    }
+}
+
+/// Splits a server-sent event stream into the payloads of its `data:` lines,
+/// wherever the network cuts the chunks.
+#[derive(Default)]
+struct SseLines {
+    buffer: Vec<u8>,
+}
+
+impl SseLines {
+    /// Adds `chunk` and returns the payloads of the lines it completes.
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(chunk);
+        let mut payloads = Vec::new();
+        while let Some(newline) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            if let Some(data) = line.trim().strip_prefix("data:") {
+                payloads.push(data.trim().to_string());
+            }
+        }
+        payloads
+    }
+}
+
+pub struct OpenAiBackend;
+
+impl OpenAiBackend {
+    /// Accepts base URLs given with or without the `/v1` suffix.
+    fn endpoint(base_url: &str, path: &str) -> String {
+        format!("{}/v1{}", base_url.trim_end_matches("/v1"), path)
+    }
+
+    /// The entries of a `/v1/models` answer.
+    fn models(json: &serde_json::Value) -> Vec<ModelInfo> {
+        json["data"]
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|model| {
+                Some(ModelInfo {
+                    name: model["id"].as_str()?.to_string(),
+                    modified_at: String::new(),
+                    size: 0,
+                    digest: String::new(),
+                    details: ModelDetails {
+                        family: model["owned_by"].as_str().unwrap_or_default().to_string(),
+                        ..ModelDetails::default()
+                    },
+                })
+            })
+            .collect()
+    }
+
+    fn messages(request: &ChatRequest) -> Vec<serde_json::Value> {
+        let mut messages = Vec::new();
+        if let Some(summary) = &request.summary {
+            messages.push(json!({
+                "role": "system",
+                "content": format!("Summary of the earlier conversation:\n{}", summary)
+            }));
+        }
+        for (prompt, response) in &request.history {
+            messages.push(json!({ "role": "user", "content": prompt }));
+            messages.push(json!({ "role": "assistant", "content": response }));
+        }
+        messages.push(json!({ "role": "user", "content": request.prompt }));
+        messages
+    }
+}
+
+impl ChatBackend for OpenAiBackend {
+    fn stream_chat(
+        &self,
+        client: reqwest::Client,
+        base_url: &str,
+        request: ChatRequest,
+    ) -> BoxStream<'static, Result<OllamaStreamProgress, Error>> {
+        let url = Self::endpoint(base_url, "/chat/completions");
+        let span = http_span("POST", &url);
+        try_channel(1, move |mut output| {
+            traced(span, async move {
+                let body = json!({
+                    "model": request.model,
+                    "messages": Self::messages(&request),
+                    "stream": true,
+                    "stream_options": { "include_usage": true }
+                });
+
+                let started = Instant::now();
+                let response = client.post(&url).json(&body).send().await?;
+                if !response.status().is_success() {
+                    let status = response.status();
+                    let text = response.text().await.unwrap_or_default();
+                    return Err(Error::Ollama(format!("{}: {}", status, text.trim())));
+                }
+
+                // Server-sent events: `data: {json}` lines, ending with `data: [DONE]`.
+                let mut stats = GenerationStats::default();
+                let mut first_token = None;
+                let mut events = SseLines::default();
+                let mut stream = response.bytes_stream();
+                'events: while let Some(chunk) = stream.next().await {
+                    for data in events.push(&chunk?) {
+                        if data == "[DONE]" {
+                            break 'events;
+                        }
+                        let event: serde_json::Value =
+                            serde_json::from_str(&data).map_err(parse_error)?;
+                        if let Some(message) = event["error"]["message"].as_str() {
+                            return Err(Error::Ollama(message.to_string()));
+                        }
+                        if let Some(token) = event["choices"][0]["delta"]["content"].as_str() {
+                            first_token.get_or_insert_with(Instant::now);
+                            stats.eval_count += 1;
+                            let _ = output
+                                .send(OllamaStreamProgress::Streaming {
+                                    token: token.to_string(),
+                                })
+                                .await;
+                        }
+                        if let Some(usage) = event.get("usage").filter(|u| !u.is_null()) {
+                            stats.prompt_eval_count = usage["prompt_tokens"].as_u64().unwrap_or(0);
+                            stats.eval_count = usage["completion_tokens"]
+                                .as_u64()
+                                .unwrap_or(stats.eval_count);
+                        }
+                    }
+                }
+
+                // The protocol reports no timings, so measure them here.
+                let first_token = first_token.unwrap_or_else(Instant::now);
+                stats.prompt_eval_duration = (first_token - started).as_nanos() as u64;
+                stats.eval_duration = first_token.elapsed().as_nanos() as u64;
+                stats.total_duration = started.elapsed().as_nanos() as u64;
+                let _ = output
+                    .send(OllamaStreamProgress::Finished {
+                        context: Vec::new(),
+                        stats,
+                    })
+                    .await;
+                Ok(())
+            })
+        })
+        .boxed()
+    }
+
+    fn complete(
+        &self,
+        client: reqwest::Client,
+        base_url: &str,
+        model: String,
+        prompt: String,
+    ) -> BoxFuture<'static, Result<String, Error>> {
+        let url = Self::endpoint(base_url, "/chat/completions");
+        let span = http_span("POST", &url);
+        traced(span, async move {
+            let body = json!({
+                "model": model,
+                "messages": [{ "role": "user", "content": prompt }],
+                "stream": false
+            });
+            let response = client
+                .post(&url)
+                .json(&body)
+                .send()
+                .await?
+                .error_for_status()?;
+            let json = response.json::<serde_json::Value>().await?;
+            Ok(json["choices"][0]["message"]["content"]
+                .as_str()
+                .unwrap_or_default()
+                .trim()
+                .to_string())
+        })
+        .boxed()
+    }
+
+    fn list_models(
+        &self,
+        client: reqwest::Client,
+        base_url: &str,
+    ) -> BoxFuture<'static, Result<Vec<ModelInfo>, Error>> {
+        let url = Self::endpoint(base_url, "/models");
+        let span = http_span("GET", &url);
+        traced(span, async move {
+            let json = client
+                .get(&url)
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<serde_json::Value>()
+                .await?;
+            Ok(Self::models(&json))
+        })
+        .boxed()
+    }
+
+    fn version(
+        &self,
+        client: reqwest::Client,
+        base_url: &str,
+    ) -> BoxFuture<'static, Result<String, Error>> {
+        let url = Self::endpoint(base_url, "/models");
+        let span = http_span("GET", &url);
+        let version = tr("backend-openai");
+        async move {
+            client.get(&url).send().await?.error_for_status()?;
+            Ok(version)
+        }
+        .instrument(span)
+        .boxed()
+    }
+
+    fn context_length(
+        &self,
+        _client: reqwest::Client,
+        _base_url: &str,
+        _model: String,
+    ) -> BoxFuture<'static, Result<Option<u64>, Error>> {
+        async { Ok(None) }.boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests;
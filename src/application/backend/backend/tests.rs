@@ -0,0 +1,42 @@
+//! Parsing OpenAI-compatible answers without a server.
+
+use super::*;
+
+#[test]
+fn joins_event_lines_split_across_chunks() {
+    let mut events = SseLines::default();
+    assert!(events.push(b"da").is_empty());
+    assert_eq!(
+        events.push(b"ta: {\"choices\":[]}\n\n: keep-alive\ndata: [DO"),
+        ["{\"choices\":[]}"]
+    );
+    assert_eq!(events.push(b"NE]\r\n"), ["[DONE]"]);
+    assert!(events.push(b"").is_empty());
+}
+
+#[test]
+fn keeps_characters_split_across_chunks() {
+    let mut events = SseLines::default();
+    let line = "data: {\"content\":\"grüß\"}\n".as_bytes();
+    let cut = line.iter().position(|b| *b == 0xc3).unwrap() + 1;
+    assert!(events.push(&line[..cut]).is_empty());
+    assert_eq!(events.push(&line[cut..]), ["{\"content\":\"grüß\"}"]);
+}
+
+#[test]
+fn lists_models_with_an_id() {
+    let answer = json!({
+        "object": "list",
+        "data": [
+            { "id": "qwen2.5-7b", "object": "model", "owned_by": "llamacpp" },
+            { "object": "model" },
+            { "id": "mistral", "object": "model" }
+        ]
+    });
+    let models = OpenAiBackend::models(&answer);
+    let names: Vec<_> = models.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, ["qwen2.5-7b", "mistral"]);
+    assert_eq!(models[0].details.family, "llamacpp");
+    assert_eq!(models[1].details.family, "");
+    assert!(OpenAiBackend::models(&json!({ "error": "nope" })).is_empty());
+}
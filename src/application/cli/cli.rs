@@ -0,0 +1,571 @@
+use crate::application::appearance::appearance::{load_themes, theme_key};
+use crate::application::application::{
+    format_size, stream_status, DownloadProgressUpdate, Error, OllamaChat, OllamaGUI,
+    OllamaStreamProgress,
+};
+use crate::application::backend::backend::ChatRequest;
+use crate::application::i18n::i18n::tr;
+use crate::application::servers::servers::{normalize_url, ServerProfile};
+use iced::futures::StreamExt;
+use iced::Size;
+use serde_json::json;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+const USAGE: &str = "\
+Usage: rusty_ollama_gui [OPTIONS] [COMMAND]
+
+Without a command the window opens.
+
+Options:
+  --url <URL>                   Server to use instead of the active profile
+  --model <MODEL>               Model to use instead of the selected one
+  --data-dir <DIR>              Folder for settings, chats and logs, the current one by default
+  --theme <THEME>               Window theme, such as Dark, \"Tokyo Night\" or a custom theme
+  --window-size <WIDTHxHEIGHT>  Window size, such as 1280x800
+  --new-chat <PROMPT>           Open the window on a new chat and send PROMPT
+  --open-chat <UUID>            Open the window on this chat
+  -h, --help                    Show this message
+
+Environment:
+  OLLAMA_HOST                   Like --url; host:port without a scheme means http
+  OLLAMA_GUI_MODEL              Like --model
+  OLLAMA_GUI_DATA_DIR           Like --data-dir
+  OLLAMA_GUI_THEME              Like --theme
+
+Options win over the environment, which wins over settings.json. Neither is
+written to settings.json unless the value is changed in the window.
+
+Commands share the chats and settings of the app:
+
+  chat <CHAT> [PROMPT]          Send PROMPT to the chat with this UUID or name
+  chat --new [PROMPT]           Start a new chat
+      --model <MODEL>           Model for a new chat, or switch an existing one
+      --name <NAME>             Name for a new chat
+  ask [PROMPT]                  One-off question; nothing is saved
+      --model <MODEL>           Model to ask instead of the selected one
+  list-chats                    List saved chats
+  export <CHAT>                 Print a chat
+      --format <markdown|json>  Output format, markdown by default
+      --output <FILE>           Write to FILE instead of stdout
+  pull <MODEL>                  Download a model
+  models                        List installed models
+  help                          Show this message
+
+PROMPT is read from stdin when it is left out.";
+
+/// Settings for this run from the options before the command and the environment.
+#[derive(Debug, Clone, Default)]
+pub struct LaunchOptions {
+    pub server_url: Option<String>,
+    pub model: Option<String>,
+    pub data_dir: Option<PathBuf>,
+    /// Name of a built-in or custom theme as `settings.json` stores it.
+    pub theme: Option<String>,
+    pub window_size: Option<Size>,
+    /// Prompt to send in a new chat once the window opens.
+    pub new_chat: Option<String>,
+    pub open_chat: Option<Uuid>,
+}
+
+impl LaunchOptions {
+    /// Reads the options up to the first argument that is not one, filling the
+    /// rest from the environment `var` looks up. Returns them with the remaining
+    /// arguments.
+    fn parse(
+        args: &[String],
+        var: impl Fn(&str) -> Option<String>,
+    ) -> Result<(Self, &[String]), String> {
+        let mut options = LaunchOptions::default();
+        let mut rest = args;
+        while let Some((flag, after)) = rest.split_first() {
+            if flag == "-h" || flag == "--help" {
+                // Treated as the `help` command.
+                break;
+            }
+            if !flag.starts_with("--") {
+                break;
+            }
+            let (value, after) = after
+                .split_first()
+                .ok_or_else(|| format!("{} needs a value", flag))?;
+            match flag.as_str() {
+                "--url" | "--host" => options.server_url = Some(server_url(value)?),
+                "--model" => options.model = Some(value.clone()),
+                "--data-dir" => options.data_dir = Some(PathBuf::from(value)),
+                "--theme" => options.theme = Some(value.clone()),
+                "--window-size" => options.window_size = Some(window_size(value)?),
+                "--new-chat" => options.new_chat = Some(value.clone()),
+                "--open-chat" => {
+                    options.open_chat = Some(
+                        Uuid::parse_str(value)
+                            .map_err(|e| format!("--open-chat {}: {}", value, e))?,
+                    )
+                }
+                other => return Err(format!("Unknown option {}", other)),
+            }
+            rest = after;
+        }
+
+        let env = |name: &str| var(name).filter(|v| !v.trim().is_empty());
+        if options.server_url.is_none() {
+            if let Some(host) = env("OLLAMA_HOST") {
+                options.server_url =
+                    Some(server_url(&host).map_err(|e| format!("OLLAMA_HOST: {}", e))?);
+            }
+        }
+        if options.model.is_none() {
+            options.model = env("OLLAMA_GUI_MODEL");
+        }
+        if options.data_dir.is_none() {
+            options.data_dir = env("OLLAMA_GUI_DATA_DIR").map(PathBuf::from);
+        }
+        if options.theme.is_none() {
+            options.theme = env("OLLAMA_GUI_THEME");
+        }
+        Ok((options, rest))
+    }
+}
+
+/// A server URL, also accepting Ollama's `host:port` shorthand for plain HTTP.
+fn server_url(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    if value.contains("://") {
+        return normalize_url(value);
+    }
+    // Like the Ollama CLI, a bare host gets Ollama's port rather than port 80.
+    let has_port = value
+        .rsplit_once(':')
+        .is_some_and(|(_, port)| port.parse::<u16>().is_ok());
+    if has_port {
+        normalize_url(&format!("http://{}", value))
+    } else {
+        normalize_url(&format!("http://{}:11434", value))
+    }
+}
+
+/// The stored name of the theme called `value`, ignoring case and spaces.
+/// Custom themes are read from the data directory, so it must be applied first.
+fn theme_name(value: &str) -> Result<String, String> {
+    let simplify = |name: &str| name.to_lowercase().replace([' ', '-', '_'], "");
+    let themes: Vec<_> = iced::Theme::ALL
+        .iter()
+        .cloned()
+        .chain(load_themes().0)
+        .collect();
+    themes
+        .iter()
+        .map(theme_key)
+        .find(|name| simplify(name) == simplify(value))
+        .ok_or_else(|| {
+            format!(
+                "Unknown theme {}. Themes: {}",
+                value,
+                themes
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })
+}
+
+fn window_size(value: &str) -> Result<Size, String> {
+    let invalid = || {
+        format!(
+            "--window-size {}: expected WIDTHxHEIGHT, such as 1280x800",
+            value
+        )
+    };
+    let (width, height) = value
+        .to_lowercase()
+        .split_once('x')
+        .map(|(w, h)| (w.trim().parse::<f32>(), h.trim().parse::<f32>()))
+        .ok_or_else(invalid)?;
+    match (width, height) {
+        (Ok(width), Ok(height)) if width > 0.0 && height > 0.0 => Ok(Size::new(width, height)),
+        _ => Err(invalid()),
+    }
+}
+
+/// What `main` should do after `run`.
+pub enum Launch {
+    Window(LaunchOptions),
+    Exit(i32),
+}
+
+/// Arguments after the command name, split into flags with values and the rest.
+struct Args {
+    flags: Vec<(String, Option<String>)>,
+    positional: Vec<String>,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut parsed = Args {
+            flags: Vec::new(),
+            positional: Vec::new(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--new" => parsed.flags.push((arg.clone(), None)),
+                "--model" | "--name" | "--format" | "--output" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("{} needs a value", arg))?;
+                    parsed.flags.push((arg.clone(), Some(value.clone())));
+                }
+                "--" => parsed.positional.extend(args.by_ref().cloned()),
+                flag if flag.starts_with("--") => return Err(format!("Unknown option {}", flag)),
+                _ => parsed.positional.push(arg.clone()),
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn has(&self, flag: &str) -> bool {
+        self.flags.iter().any(|(name, _)| name == flag)
+    }
+
+    fn value(&self, flag: &str) -> Option<&str> {
+        self.flags
+            .iter()
+            .find(|(name, _)| name == flag)
+            .and_then(|(_, value)| value.as_deref())
+    }
+}
+
+/// Reads the options and moves to the data directory, then runs the command if
+/// there is one. Without a command the window should open.
+pub fn run(args: &[String]) -> Launch {
+    let (mut options, args) = match LaunchOptions::parse(args, |name| std::env::var(name).ok()) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return Launch::Exit(2);
+        }
+    };
+    // Settings, chats and logs are all found relative to the working directory.
+    if let Some(dir) = &options.data_dir {
+        if let Err(e) = fs::create_dir_all(dir).and_then(|_| std::env::set_current_dir(dir)) {
+            eprintln!("{}: {}", dir.display(), e);
+            return Launch::Exit(1);
+        }
+    }
+    if let Some(theme) = &options.theme {
+        match theme_name(theme) {
+            Ok(name) => options.theme = Some(name),
+            Err(e) => {
+                eprintln!("{}\n\n{}", e, USAGE);
+                return Launch::Exit(2);
+            }
+        }
+    }
+    let Some((command, rest)) = args.split_first() else {
+        return Launch::Window(options);
+    };
+    let result = Args::parse(rest).and_then(|rest| match command.as_str() {
+        "chat" => chat(&options, &rest),
+        "ask" => ask(&options, &rest),
+        "list-chats" => list_chats(),
+        "export" => export(&rest),
+        "pull" => pull(&options, &rest),
+        "models" => models(&options),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        other => Err(format!("Unknown command {}\n\n{}", other, USAGE)),
+    });
+    Launch::Exit(match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    })
+}
+
+/// The saved server profiles, the active one first. A `--url` that no profile
+/// has comes first as a profile of its own.
+fn servers(options: &LaunchOptions) -> Vec<ServerProfile> {
+    let mut settings = OllamaGUI::load_settings();
+    let mut active = settings.prepare_servers();
+    let mut servers = settings.servers().to_vec();
+    if let Some(url) = &options.server_url {
+        match servers.iter().find(|server| &server.url == url) {
+            Some(server) => active = server.id,
+            None => {
+                let server = ServerProfile::new(&tr("server-command-line"), url);
+                active = server.id;
+                servers.push(server);
+            }
+        }
+    }
+    servers.sort_by_key(|server| server.id != active);
+    servers
+}
+
+fn selected_model(options: &LaunchOptions) -> String {
+    options
+        .model
+        .clone()
+        .unwrap_or_else(|| OllamaGUI::load_settings().selected_model().to_string())
+}
+
+/// The prompt from the remaining arguments, or stdin when there are none.
+fn prompt(words: &[String]) -> Result<String, String> {
+    let prompt = if words.is_empty() {
+        let mut input = String::new();
+        io::stdin()
+            .read_to_string(&mut input)
+            .map_err(|e| e.to_string())?;
+        input
+    } else {
+        words.join(" ")
+    };
+    let prompt = prompt.trim().to_string();
+    if prompt.is_empty() {
+        return Err("The prompt is empty".to_string());
+    }
+    Ok(prompt)
+}
+
+/// Finds a chat by UUID, or by name when exactly one chat has it.
+fn find_chat(chats: Vec<OllamaChat>, query: &str) -> Result<OllamaChat, String> {
+    let mut matches: Vec<OllamaChat> = chats
+        .into_iter()
+        .filter(|chat| {
+            chat.uuid.to_string() == query.to_lowercase()
+                || chat.display_name.eq_ignore_ascii_case(query)
+        })
+        .collect();
+    match matches.len() {
+        0 => Err(format!("No chat matches {}", query)),
+        1 => Ok(matches.remove(0)),
+        _ => Err(format!(
+            "{} chats are named {}, use one of their UUIDs:\n{}",
+            matches.len(),
+            query,
+            matches
+                .iter()
+                .map(|chat| chat.uuid.to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        )),
+    }
+}
+
+/// Streams the reply to `request`, printing tokens as they arrive and handing
+/// every update to `on_progress`.
+fn stream(
+    server: &ServerProfile,
+    request: ChatRequest,
+    mut on_progress: impl FnMut(Result<OllamaStreamProgress, Error>),
+) -> Result<(), String> {
+    let client = server.client()?;
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    runtime.block_on(async {
+        let mut stream = server.backend().stream_chat(client, &server.url, request);
+        let mut stdout = io::stdout();
+        while let Some(progress) = stream.next().await {
+            match progress {
+                Ok(OllamaStreamProgress::Streaming { token }) => {
+                    print!("{}", token);
+                    let _ = stdout.flush();
+                    on_progress(Ok(OllamaStreamProgress::Streaming { token }));
+                }
+                Ok(finished) => {
+                    println!();
+                    on_progress(Ok(finished));
+                    return Ok(());
+                }
+                Err(e) => {
+                    println!();
+                    let message = format!("Error: {}", e);
+                    on_progress(Err(e));
+                    return Err(message);
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+fn chat(options: &LaunchOptions, args: &Args) -> Result<(), String> {
+    let servers = servers(options);
+    let (query, words) = if args.has("--new") {
+        (None, &args.positional[..])
+    } else {
+        let (query, words) = args
+            .positional
+            .split_first()
+            .ok_or_else(|| format!("Which chat?\n\n{}", USAGE))?;
+        (Some(query), words)
+    };
+    let prompt = prompt(words)?;
+
+    let mut chat = match query {
+        Some(query) => {
+            let mut chat = find_chat(OllamaChat::load_all(), query)?;
+            if let Some(model) = args.value("--model") {
+                chat.change_model(model.to_string());
+            }
+            chat
+        }
+        None => {
+            let model = args
+                .value("--model")
+                .map(str::to_string)
+                .unwrap_or_else(|| selected_model(options));
+            let mut chat = OllamaChat::new(model, servers[0].id);
+            if let Some(name) = args.value("--name") {
+                chat.start_rename();
+                chat.update_temp_name(name.to_string());
+                chat.finish_rename();
+            }
+            chat
+        }
+    };
+
+    let server = servers
+        .iter()
+        .find(|server| server.id == chat.server)
+        .unwrap_or(&servers[0])
+        .clone();
+    chat.send(prompt);
+    eprintln!("{} ({})", chat.display_name, chat.uuid);
+
+    // `progress` saves the chat once the reply is complete, so the window shows
+    // the new turn the next time it loads the chat.
+    stream(&server, chat.chat_request(), |progress| {
+        chat.progress(progress)
+    })
+}
+
+fn ask(options: &LaunchOptions, args: &Args) -> Result<(), String> {
+    let servers = servers(options);
+    let request = ChatRequest {
+        model: args
+            .value("--model")
+            .map(str::to_string)
+            .unwrap_or_else(|| selected_model(options)),
+        prompt: prompt(&args.positional)?,
+        history: Vec::new(),
+        summary: None,
+        context: None,
+        num_ctx: None,
+    };
+    stream(&servers[0], request, |_| {})
+}
+
+fn list_chats() -> Result<(), String> {
+    let mut chats = OllamaChat::load_all();
+    chats.sort_by(|a, b| a.display_name.cmp(&b.display_name));
+    for chat in chats {
+        println!(
+            "{}  {:>4} turns  {:<24}  {}",
+            chat.uuid,
+            chat.turns(),
+            chat.model,
+            chat.display_name
+        );
+    }
+    Ok(())
+}
+
+fn export(args: &Args) -> Result<(), String> {
+    let query = args
+        .positional
+        .first()
+        .ok_or_else(|| format!("Which chat?\n\n{}", USAGE))?;
+    let chat = find_chat(OllamaChat::load_all(), query)?;
+    let text = match args.value("--format").unwrap_or("markdown") {
+        "markdown" | "md" => chat.to_markdown(),
+        "json" => chat.to_json(),
+        other => return Err(format!("Unknown format {}", other)),
+    };
+    match args.value("--output") {
+        Some(path) => fs::write(path, text).map_err(|e| format!("{}: {}", path, e)),
+        None => {
+            print!("{}", text);
+            Ok(())
+        }
+    }
+}
+
+fn pull(options: &LaunchOptions, args: &Args) -> Result<(), String> {
+    let model = args
+        .positional
+        .first()
+        .ok_or_else(|| format!("Which model?\n\n{}", USAGE))?;
+    let server = &servers(options)[0];
+    if !server.backend.manages_models() {
+        return Err(format!(
+            "{} is an {} server, which cannot pull models",
+            server.name, server.backend
+        ));
+    }
+    let client = server.client()?;
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    runtime.block_on(async {
+        let mut stream = Box::pin(stream_status(
+            client,
+            server.endpoint("/api/pull"),
+            json!({ "model": model, "stream": true }),
+        ));
+        let mut last_status = String::new();
+        while let Some(progress) = stream.next().await {
+            match progress {
+                Ok(DownloadProgressUpdate::Progress {
+                    status,
+                    total,
+                    completed,
+                    ..
+                }) => {
+                    // Print each status once, and a progress line per percent.
+                    let line = match (completed * 100).checked_div(total) {
+                        Some(percent) => {
+                            format!("{} {}% of {}", status, percent, format_size(total))
+                        }
+                        None => status,
+                    };
+                    if line != last_status {
+                        eprintln!("{}", line);
+                        last_status = line;
+                    }
+                }
+                Ok(DownloadProgressUpdate::Finished) => {
+                    println!("Pulled {}", model);
+                    return Ok(());
+                }
+                Err(e) => return Err(format!("Error: {}", e)),
+            }
+        }
+        Ok(())
+    })
+}
+
+fn models(options: &LaunchOptions) -> Result<(), String> {
+    let server = &servers(options)[0];
+    let client = server.client()?;
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    let models = runtime
+        .block_on(server.backend().list_models(client, &server.url))
+        .map_err(|e| format!("Error: {}", e))?;
+    for model in models {
+        println!(
+            "{:<40} {:>10}  {}",
+            model.name,
+            format_size(model.size),
+            model.details.parameter_size
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
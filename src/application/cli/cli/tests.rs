@@ -0,0 +1,74 @@
+//! Reading launch options from the arguments and the environment.
+
+use super::*;
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+/// An environment holding only `vars`.
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: Vec<(String, String)> = vars
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    move |name| {
+        vars.iter()
+            .find(|(var, _)| var == name)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+#[test]
+fn options_win_over_the_environment() {
+    let args = args(&["--url", "gpu.lan", "--model", "llama3.2", "chat", "--new"]);
+    let vars = [
+        ("OLLAMA_HOST", "other:8080"),
+        ("OLLAMA_GUI_MODEL", "mistral"),
+        ("OLLAMA_GUI_THEME", "Nord"),
+    ];
+    let (options, rest) = LaunchOptions::parse(&args, env(&vars)).unwrap();
+    assert_eq!(options.server_url.as_deref(), Some("http://gpu.lan:11434"));
+    assert_eq!(options.model.as_deref(), Some("llama3.2"));
+    assert_eq!(options.theme.as_deref(), Some("Nord"));
+    assert_eq!(options.data_dir, None);
+    assert_eq!(rest, ["chat", "--new"]);
+}
+
+#[test]
+fn the_environment_fills_what_the_options_leave_out() {
+    let vars = [
+        ("OLLAMA_HOST", "other:8080"),
+        ("OLLAMA_GUI_MODEL", " "),
+        ("OLLAMA_GUI_DATA_DIR", "/tmp/ollama-gui"),
+    ];
+    let (options, rest) = LaunchOptions::parse(&[], env(&vars)).unwrap();
+    assert_eq!(options.server_url.as_deref(), Some("http://other:8080"));
+    assert_eq!(options.model, None);
+    assert_eq!(options.data_dir, Some(PathBuf::from("/tmp/ollama-gui")));
+    assert!(rest.is_empty());
+
+    let error = LaunchOptions::parse(&[], env(&[("OLLAMA_HOST", "http://a/?b")])).unwrap_err();
+    assert!(error.starts_with("OLLAMA_HOST: "), "{}", error);
+}
+
+#[test]
+fn rejects_unknown_and_incomplete_options() {
+    assert!(LaunchOptions::parse(&args(&["--colour", "red"]), env(&[])).is_err());
+    assert!(LaunchOptions::parse(&args(&["--model"]), env(&[])).is_err());
+    let args = args(&["--help", "--model", "x"]);
+    let (options, rest) = LaunchOptions::parse(&args, env(&[])).unwrap();
+    assert_eq!(options.model, None);
+    assert_eq!(rest.len(), 3);
+}
+
+#[test]
+fn server_urls_accept_the_ollama_host_shorthand() {
+    assert_eq!(server_url("gpu.lan").unwrap(), "http://gpu.lan:11434");
+    assert_eq!(server_url("0.0.0.0:8080").unwrap(), "http://0.0.0.0:8080");
+    assert_eq!(
+        server_url("https://ollama.example.com/").unwrap(),
+        "https://ollama.example.com"
+    );
+    assert!(server_url("ftp://gpu.lan").is_err());
+}
@@ -0,0 +1,236 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Write as _};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::{Event, Instrument, Level, Span, Subscriber};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt as log_fmt, Layer};
+
+pub const LOG_DIR: &str = "./logs";
+
+/// Daily log files kept before the oldest is deleted.
+const MAX_LOG_FILES: usize = 7;
+
+/// Events kept in memory for the Diagnostics view.
+const MAX_RECENT: usize = 500;
+
+static RECENT: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+
+/// An event as shown in the Diagnostics view.
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub time: SystemTime,
+    pub level: Level,
+    /// The message with its fields, prefixed by the spans it happened in.
+    pub message: String,
+}
+
+impl fmt::Display for LogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self
+            .time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            % 86_400;
+        write!(
+            f,
+            "{:02}:{:02}:{:02} {:>5} {}",
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60,
+            self.level,
+            self.message
+        )
+    }
+}
+
+/// Sends this crate's events to a daily log file in `LOG_DIR` and keeps the
+/// recent ones in memory. The returned guard flushes the file when dropped.
+pub fn init() -> Option<WorkerGuard> {
+    let _ = std::fs::create_dir_all(LOG_DIR);
+    let file = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(env!("CARGO_CRATE_NAME"))
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(LOG_DIR);
+    let (file_layer, guard, file_error) = match file {
+        Ok(file) => {
+            let (writer, guard) = tracing_appender::non_blocking(file);
+            let layer = log_fmt::layer()
+                .with_writer(writer)
+                .with_ansi(false)
+                .with_filter(
+                    Targets::new()
+                        .with_target(env!("CARGO_CRATE_NAME"), Level::DEBUG)
+                        .with_default(Level::WARN),
+                );
+            (Some(layer), Some(guard), None)
+        }
+        Err(e) => (None, None, Some(e)),
+    };
+
+    let _ = tracing_subscriber::registry()
+        .with(file_layer)
+        .with(RecentEvents.with_filter(LevelFilter::INFO))
+        .try_init();
+    if let Some(e) = file_error {
+        tracing::warn!(error = %e, "could not open a log file in {}", LOG_DIR);
+    }
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "started");
+    guard
+}
+
+/// The events kept for the Diagnostics view, oldest first.
+pub fn recent() -> Vec<LogEntry> {
+    RECENT
+        .lock()
+        .map(|recent| recent.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Where the log files are, as an absolute path when it exists.
+pub fn log_dir() -> String {
+    std::fs::canonicalize(LOG_DIR)
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|_| LOG_DIR.to_string())
+}
+
+/// Span for one HTTP request. Credentials and the query string stay out of the URL.
+pub fn http_span(method: &'static str, url: &str) -> Span {
+    tracing::info_span!("http", method, url = %redact_url(url))
+}
+
+/// Runs `request` inside `span`, logging how long it took and whether it failed.
+pub fn traced<F, T, E>(span: Span, request: F) -> impl Future<Output = Result<T, E>>
+where
+    F: Future<Output = Result<T, E>>,
+    E: fmt::Debug,
+{
+    async move {
+        let started = Instant::now();
+        let result = request.await;
+        log_result(&result, started);
+        result
+    }
+    .instrument(span)
+}
+
+fn log_result<T, E: fmt::Debug>(result: &Result<T, E>, started: Instant) {
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+    match result {
+        Ok(_) => tracing::debug!(elapsed_ms, "finished"),
+        Err(e) => tracing::warn!(elapsed_ms, error = ?e, "failed"),
+    }
+}
+
+/// `url` without a user name, password or query string.
+pub fn redact_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) => {
+            let _ = parsed.set_username("");
+            let _ = parsed.set_password(None);
+            parsed.set_query(None);
+            parsed.to_string()
+        }
+        Err(_) => "[unparsable url]".to_string(),
+    }
+}
+
+/// `text` with every occurrence of `secrets` blanked out. Very short values are
+/// skipped since they would match ordinary text.
+pub fn redact(text: &str, secrets: &[&str]) -> String {
+    let mut secrets: Vec<&str> = secrets
+        .iter()
+        .map(|secret| secret.trim())
+        .filter(|secret| secret.len() >= 4)
+        .collect();
+    // Longest first, so a secret containing another is replaced whole.
+    secrets.sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+    secrets.into_iter().fold(text.to_string(), |text, secret| {
+        text.replace(secret, "[redacted]")
+    })
+}
+
+/// Collects a message and `name=value` pairs from an event or span.
+#[derive(Default)]
+struct Fields {
+    message: String,
+    rest: String,
+}
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            let _ = write!(self.rest, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            let _ = write!(self.rest, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// The fields a span was created with, stored on the span for `RecentEvents`.
+struct SpanFields(String);
+
+/// Keeps the last `MAX_RECENT` events in `RECENT`.
+struct RecentEvents;
+
+impl<S> Layer<S> for RecentEvents
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = Fields::default();
+        attrs.record(&mut fields);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanFields(fields.rest));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut message = String::new();
+        if let Some(scope) = ctx.event_scope(event) {
+            for span in scope.from_root() {
+                message.push_str(span.name());
+                if let Some(SpanFields(fields)) = span.extensions().get::<SpanFields>() {
+                    if !fields.is_empty() {
+                        let _ = write!(message, "{{{}}}", fields.trim_start());
+                    }
+                }
+                message.push_str(": ");
+            }
+        }
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        message.push_str(&fields.message);
+        message.push_str(&fields.rest);
+
+        if let Ok(mut recent) = RECENT.lock() {
+            if recent.len() == MAX_RECENT {
+                recent.pop_front();
+            }
+            recent.push_back(LogEntry {
+                time: SystemTime::now(),
+                level: *event.metadata().level(),
+                message,
+            });
+        }
+    }
+}
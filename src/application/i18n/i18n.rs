@@ -0,0 +1,125 @@
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use serde::{Deserialize, Serialize};
+use std::sync::{OnceLock, RwLock};
+use unic_langid::LanguageIdentifier;
+
+/// Message files built into the binary, one per shipped language.
+const LOCALES: [(Language, &str); 2] = [
+    (
+        Language::English,
+        include_str!("../../../locales/en/main.ftl"),
+    ),
+    (
+        Language::German,
+        include_str!("../../../locales/de/main.ftl"),
+    ),
+];
+
+static CURRENT: RwLock<Language> = RwLock::new(Language::English);
+
+static BUNDLES: OnceLock<Vec<(Language, FluentBundle<FluentResource>)>> = OnceLock::new();
+
+/// A language the interface is translated into, stored by its code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "de")]
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+
+    /// The shipped language matching the system locale, English otherwise.
+    pub fn from_system() -> Self {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        Self::ALL
+            .into_iter()
+            .find(|language| locale.starts_with(language.code()))
+            .unwrap_or_default()
+    }
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Each language is listed under its own name.
+        match self {
+            Language::English => write!(f, "English"),
+            Language::German => write!(f, "Deutsch"),
+        }
+    }
+}
+
+pub fn language() -> Language {
+    *CURRENT.read().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn set_language(language: Language) {
+    *CURRENT.write().unwrap_or_else(|e| e.into_inner()) = language;
+}
+
+/// The message `key` in the current language.
+pub fn tr(key: &str) -> String {
+    format(language(), key, None)
+}
+
+/// The message `key` in the current language, filled in with `args`.
+pub fn tr_args(key: &str, args: &[(&str, FluentValue)]) -> String {
+    let args: FluentArgs = args.iter().cloned().collect();
+    format(language(), key, Some(&args))
+}
+
+/// Missing messages fall back to English, then to the key itself.
+fn format(language: Language, key: &str, args: Option<&FluentArgs>) -> String {
+    let bundles = BUNDLES.get_or_init(|| {
+        LOCALES
+            .iter()
+            .map(|(language, source)| (*language, bundle(*language, source)))
+            .collect()
+    });
+    [language, Language::English]
+        .iter()
+        .filter_map(|language| bundles.iter().find(|(l, _)| l == language))
+        .find_map(|(_, bundle)| {
+            let pattern = bundle.get_message(key)?.value()?;
+            let mut errors = Vec::new();
+            Some(
+                bundle
+                    .format_pattern(pattern, args, &mut errors)
+                    .into_owned(),
+            )
+        })
+        .unwrap_or_else(|| key.to_string())
+}
+
+fn bundle(language: Language, source: &str) -> FluentBundle<FluentResource> {
+    let resource = FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, e)| {
+        tracing::error!(language = language.code(), errors = ?e, "invalid messages");
+        resource
+    });
+    let id: LanguageIdentifier = language.code().parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![id]);
+    // Isolation marks around arguments show up as boxes in some fonts.
+    bundle.set_use_isolating(false);
+    if let Err(e) = bundle.add_resource(resource) {
+        tracing::error!(language = language.code(), errors = ?e, "duplicate messages");
+    }
+    bundle
+}
+
+#[cfg(test)]
+mod tests;
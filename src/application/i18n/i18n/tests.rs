@@ -0,0 +1,251 @@
+//! Checks the shipped message files against each other and against the keys
+//! the source code asks for.
+
+use super::*;
+use crate::application::appearance::appearance::ThemeColor;
+use crate::application::keymap::keymap::Action;
+use fluent_syntax::ast::{Entry, Expression, InlineExpression, Pattern, PatternElement};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// Every message in `source` with the variables its value uses.
+fn messages(source: &str) -> BTreeMap<String, BTreeSet<String>> {
+    let resource = fluent_syntax::parser::parse(source).unwrap_or_else(|(_, errors)| {
+        panic!("{:?}", errors);
+    });
+    resource
+        .body
+        .iter()
+        .filter_map(|entry| match entry {
+            Entry::Message(message) => {
+                let mut variables = BTreeSet::new();
+                if let Some(value) = &message.value {
+                    pattern_variables(value, &mut variables);
+                }
+                Some((message.id.name.to_string(), variables))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn pattern_variables(pattern: &Pattern<&str>, variables: &mut BTreeSet<String>) {
+    for element in &pattern.elements {
+        if let PatternElement::Placeable { expression } = element {
+            match expression {
+                Expression::Inline(inline) => inline_variable(inline, variables),
+                Expression::Select { selector, variants } => {
+                    inline_variable(selector, variables);
+                    for variant in variants {
+                        pattern_variables(&variant.value, variables);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn inline_variable(expression: &InlineExpression<&str>, variables: &mut BTreeSet<String>) {
+    if let InlineExpression::VariableReference { id } = expression {
+        variables.insert(id.name.to_string());
+    }
+}
+
+/// The string literal opening `rest`, after any whitespace.
+fn leading_literal(rest: &str) -> Option<&str> {
+    let rest = rest.trim_start().strip_prefix('"')?;
+    let mut escaped = false;
+    let end = rest.find(|c| {
+        let end = c == '"' && !escaped;
+        escaped = c == '\\' && !escaped;
+        end
+    })?;
+    Some(&rest[..end])
+}
+
+/// Each `name(` call in `source` that is not the tail of a longer name, like
+/// `attr` for `tr`, with the text after the parenthesis.
+fn calls<'a>(source: &'a str, name: &str) -> Vec<(usize, &'a str)> {
+    let call = format!("{}(", name);
+    source
+        .match_indices(&call)
+        .filter(|(start, _)| {
+            let before = source[..*start].chars().next_back();
+            name.starts_with('.') || !before.is_some_and(|c| c.is_alphanumeric() || c == '_')
+        })
+        .map(|(start, _)| (start, &source[start + call.len()..]))
+        .collect()
+}
+
+/// The Rust sources under `dir`, leaving out tests.
+fn sources(dir: &Path, files: &mut Vec<(std::path::PathBuf, String)>) {
+    for path in fs_entries(dir) {
+        if path.is_dir() {
+            sources(&path, files);
+        } else if path.extension().is_some_and(|e| e == "rs")
+            && path.file_name().is_some_and(|name| name != "tests.rs")
+        {
+            let source = std::fs::read_to_string(&path).unwrap();
+            files.push((path, source));
+        }
+    }
+}
+
+/// Keys passed to `tr` and `tr_args` as string literals.
+fn keys_used_in(files: &[(std::path::PathBuf, String)]) -> BTreeSet<String> {
+    let mut keys = BTreeSet::new();
+    for (_, source) in files {
+        for name in ["tr", "tr_args"] {
+            for (_, rest) in calls(source, name) {
+                if let Some(key) = leading_literal(rest) {
+                    keys.insert(key.to_string());
+                }
+            }
+        }
+    }
+    keys
+}
+
+/// Words written straight into widgets, or into the notices, errors, statuses
+/// and names the interface shows, instead of looked up, as `file:line`. The
+/// command line prints English like its usage text, so `cli.rs` is left out.
+fn untranslated_literals_in(files: &[(std::path::PathBuf, String)]) -> Vec<String> {
+    let mut found = Vec::new();
+    for (path, source) in files {
+        if path.ends_with("cli.rs") {
+            continue;
+        }
+        let mut starts = Vec::new();
+        for name in [
+            "button",
+            "text",
+            "text_input",
+            "checkbox",
+            ".placeholder",
+            "section",
+            "ServerProfile::new",
+        ] {
+            starts.extend(calls(source, name));
+        }
+        for setter in [
+            "notice = Some(",
+            "error = Some(",
+            "status = ",
+            "display_name = ",
+            "errors.push(",
+            "errors.insert(0, ",
+            "Failed(",
+            "map_err(|_| ",
+            "map_err(|e| ",
+            "ok_or_else(|| ",
+            "Err(",
+        ] {
+            starts.extend(
+                source
+                    .match_indices(setter)
+                    .map(|(start, _)| (start, &source[start + setter.len()..])),
+            );
+        }
+        for (start, rest) in starts {
+            let rest = rest.trim_start();
+            let rest = rest.strip_prefix("format!(").unwrap_or(rest);
+            if leading_literal(rest).is_some_and(|s| s.chars().any(char::is_alphabetic)) {
+                let line = source[..start].lines().count();
+                found.push(format!("{}:{}", path.display(), line));
+            }
+        }
+    }
+    found.sort();
+    found
+}
+
+fn fs_entries(dir: &Path) -> Vec<std::path::PathBuf> {
+    std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect()
+}
+
+#[test]
+fn every_locale_has_every_key_with_the_same_variables() {
+    let (_, english) = LOCALES[0];
+    let english = messages(english);
+    for (language, source) in &LOCALES[1..] {
+        let translated = messages(source);
+        let missing: Vec<_> = english
+            .keys()
+            .filter(|key| !translated.contains_key(*key))
+            .collect();
+        let extra: Vec<_> = translated
+            .keys()
+            .filter(|key| !english.contains_key(*key))
+            .collect();
+        assert!(
+            missing.is_empty(),
+            "{:?} is missing {:?}",
+            language,
+            missing
+        );
+        assert!(
+            extra.is_empty(),
+            "{:?} has unknown keys {:?}",
+            language,
+            extra
+        );
+        for (key, variables) in &english {
+            assert_eq!(
+                &translated[key], variables,
+                "{:?} uses other variables in {}",
+                language, key
+            );
+        }
+    }
+}
+
+#[test]
+fn every_key_in_the_source_exists() {
+    let mut files = Vec::new();
+    sources(
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+        &mut files,
+    );
+    let english = messages(LOCALES[0].1);
+    // Actions and theme colors look their keys up through a variable.
+    let used: BTreeSet<_> = keys_used_in(&files)
+        .into_iter()
+        .chain(Action::ALL.iter().map(|a| a.key().to_string()))
+        .chain(ThemeColor::ALL.iter().map(|c| c.key().to_string()))
+        .collect();
+    let missing: Vec<_> = used
+        .iter()
+        .filter(|key| !english.contains_key(*key))
+        .collect();
+    assert!(!used.is_empty());
+    assert!(missing.is_empty(), "no messages for {:?}", missing);
+}
+
+#[test]
+fn the_interface_shows_no_untranslated_words() {
+    let mut files = Vec::new();
+    sources(
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+        &mut files,
+    );
+    let literals = untranslated_literals_in(&files);
+    assert!(literals.is_empty(), "use tr for the text at {:?}", literals);
+}
+
+#[test]
+fn formats_plurals_and_falls_back_to_the_key() {
+    let args: FluentArgs = [
+        ("failures", FluentValue::from(1)),
+        ("error", "timeout".into()),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        format(Language::German, "health-failed-checks", Some(&args)),
+        "1 fehlgeschlagene Prüfung: timeout"
+    );
+    assert_eq!(format(Language::German, "missing-key", None), "missing-key");
+}
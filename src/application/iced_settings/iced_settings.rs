@@ -1,21 +1,71 @@
+use crate::application::appearance::appearance::FontSettings;
 use iced::window::settings::PlatformSpecific;
 use iced::window::Position;
-use iced::{Settings, Size};
+use iced::{Pixels, Point, Settings, Size};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
 
-pub fn settings() -> Settings {
-    Settings::default()
+pub const DEFAULT_SIZE: Size = Size::new(1080.0, 720.0);
+pub const MIN_SIZE: Size = Size::new(960.0, 544.0);
+
+/// The position `restore_position` starts from. iced takes that function as a
+/// plain `fn`, so it cannot capture the geometry.
+static SAVED_POSITION: OnceLock<Point> = OnceLock::new();
+
+/// Size and place of the window when it was last closed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub width: f32,
+    pub height: f32,
+    /// Top-left corner; `None` centers the window.
+    #[serde(default)]
+    pub position: Option<(f32, f32)>,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+impl Default for WindowGeometry {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_SIZE.width,
+            height: DEFAULT_SIZE.height,
+            position: None,
+            maximized: false,
+        }
+    }
+}
+
+impl WindowGeometry {
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+}
+
+pub fn settings(fonts: &FontSettings) -> Settings {
+    Settings {
+        default_font: fonts.ui_font(),
+        default_text_size: Pixels(fonts.size),
+        ..Settings::default()
+    }
 }
 
-pub fn windows_settings() -> iced::window::Settings {
+/// Window settings restoring `geometry`, opening at `size` instead when one is given.
+pub fn windows_settings(size: Option<Size>, geometry: &WindowGeometry) -> iced::window::Settings {
     let icon = iced::window::icon::from_file_data(
         include_bytes!("../../../images/logo.png"),
         Some(iced::advanced::graphics::image::image_rs::ImageFormat::Png),
     )
     .unwrap();
     iced::window::Settings {
-        size: Size::new(1080.0, 720.0),
-        position: Position::Centered,
-        min_size: Some(Size::new(960.0, 544.0)),
+        size: size.unwrap_or(geometry.size()),
+        position: match geometry.position {
+            Some((x, y)) => {
+                let _ = SAVED_POSITION.set(Point::new(x, y));
+                Position::SpecificWith(restore_position)
+            }
+            None => Position::Centered,
+        },
+        min_size: Some(MIN_SIZE),
         max_size: None,
         visible: true,
         resizable: true,
@@ -24,6 +74,44 @@ pub fn windows_settings() -> iced::window::Settings {
         level: iced::window::Level::Normal,
         icon: Some(icon),
         platform_specific: PlatformSpecific::default(),
-        exit_on_close_request: true,
+        // The window saves its geometry before it closes.
+        exit_on_close_request: false,
     }
 }
+
+/// Where the window saved at `SAVED_POSITION` reopens. iced only reports the
+/// primary monitor, so a window closed on another one comes back on it.
+fn restore_position(window: Size, monitor: Size) -> Point {
+    match SAVED_POSITION.get() {
+        Some(saved) => fit_on_monitor(*saved, window, monitor),
+        None => centered(window, monitor),
+    }
+}
+
+/// `saved` moved just far enough that the whole window fits on the monitor,
+/// or centered if it would not be visible there at all.
+fn fit_on_monitor(saved: Point, window: Size, monitor: Size) -> Point {
+    let visible = saved.x < monitor.width
+        && saved.y < monitor.height
+        && saved.x + window.width > 0.0
+        && saved.y + window.height > 0.0;
+    if !visible {
+        return centered(window, monitor);
+    }
+    Point::new(
+        saved.x.clamp(0.0, (monitor.width - window.width).max(0.0)),
+        saved
+            .y
+            .clamp(0.0, (monitor.height - window.height).max(0.0)),
+    )
+}
+
+fn centered(window: Size, monitor: Size) -> Point {
+    Point::new(
+        (monitor.width - window.width).max(0.0) / 2.0,
+        (monitor.height - window.height).max(0.0) / 2.0,
+    )
+}
+
+#[cfg(test)]
+mod tests;
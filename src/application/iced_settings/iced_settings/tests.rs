@@ -0,0 +1,47 @@
+//! Placing a restored window on the monitor it opens on.
+
+use super::*;
+
+const MONITOR: Size = Size::new(1920.0, 1080.0);
+const WINDOW: Size = Size::new(800.0, 600.0);
+
+#[test]
+fn keeps_a_position_that_fits() {
+    let saved = Point::new(100.0, 50.0);
+    assert_eq!(fit_on_monitor(saved, WINDOW, MONITOR), saved);
+}
+
+#[test]
+fn pulls_a_partly_visible_window_onto_the_monitor() {
+    assert_eq!(
+        fit_on_monitor(Point::new(1500.0, 700.0), WINDOW, MONITOR),
+        Point::new(1120.0, 480.0)
+    );
+    assert_eq!(
+        fit_on_monitor(Point::new(-300.0, -20.0), WINDOW, MONITOR),
+        Point::new(0.0, 0.0)
+    );
+}
+
+#[test]
+fn centers_a_window_left_on_another_monitor() {
+    let center = Point::new(560.0, 240.0);
+    assert_eq!(
+        fit_on_monitor(Point::new(2400.0, 100.0), WINDOW, MONITOR),
+        center
+    );
+    assert_eq!(
+        fit_on_monitor(Point::new(-900.0, 100.0), WINDOW, MONITOR),
+        center
+    );
+}
+
+#[test]
+fn a_window_larger_than_the_monitor_starts_at_its_corner() {
+    let window = Size::new(2560.0, 1440.0);
+    assert_eq!(
+        fit_on_monitor(Point::new(200.0, 100.0), window, MONITOR),
+        Point::ORIGIN
+    );
+    assert_eq!(centered(window, MONITOR), Point::ORIGIN);
+}
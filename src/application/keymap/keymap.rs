@@ -0,0 +1,302 @@
+use crate::application::i18n::i18n::{tr, tr_args};
+use iced::keyboard::{Key, Modifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::str::FromStr;
+
+const KEYMAP_PATH: &str = "./settings/keymap.json";
+
+/// Something a keyboard shortcut or the command palette can do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    NewChat,
+    NextChat,
+    PreviousChat,
+    FocusInput,
+    StopGeneration,
+    Regenerate,
+    OpenSettings,
+    Search,
+    CommandPalette,
+    ToggleSidebar,
+    ZoomIn,
+    ZoomOut,
+    ResetZoom,
+}
+
+impl Action {
+    pub const ALL: [Action; 13] = [
+        Action::NewChat,
+        Action::NextChat,
+        Action::PreviousChat,
+        Action::FocusInput,
+        Action::StopGeneration,
+        Action::Regenerate,
+        Action::OpenSettings,
+        Action::Search,
+        Action::CommandPalette,
+        Action::ToggleSidebar,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ResetZoom,
+    ];
+
+    fn default_keys(&self) -> &'static str {
+        match self {
+            Action::NewChat => "Ctrl+N",
+            Action::NextChat => "Ctrl+Tab",
+            Action::PreviousChat => "Ctrl+Shift+Tab",
+            Action::FocusInput => "Ctrl+L",
+            Action::StopGeneration => "Escape",
+            Action::Regenerate => "Ctrl+R",
+            Action::OpenSettings => "Ctrl+,",
+            Action::Search => "Ctrl+F",
+            Action::CommandPalette => "Ctrl+K",
+            Action::ToggleSidebar => "Ctrl+B",
+            Action::ZoomIn => "Ctrl+=",
+            Action::ZoomOut => "Ctrl+-",
+            Action::ResetZoom => "Ctrl+0",
+        }
+    }
+
+    /// The message naming this action in the interface.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Action::NewChat => "action-new-chat",
+            Action::NextChat => "action-next-chat",
+            Action::PreviousChat => "action-previous-chat",
+            Action::FocusInput => "action-focus-input",
+            Action::StopGeneration => "action-stop-generation",
+            Action::Regenerate => "action-regenerate",
+            Action::OpenSettings => "action-open-settings",
+            Action::Search => "action-search",
+            Action::CommandPalette => "action-command-palette",
+            Action::ToggleSidebar => "action-toggle-sidebar",
+            Action::ZoomIn => "action-zoom-in",
+            Action::ZoomOut => "action-zoom-out",
+            Action::ResetZoom => "action-reset-zoom",
+        }
+    }
+}
+
+impl std::fmt::Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", tr(self.key()))
+    }
+}
+
+/// A key with modifiers, written like `Ctrl+Shift+Tab`. `Ctrl` means Cmd on macOS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyCombo {
+    ctrl: bool,
+    shift: bool,
+    alt: bool,
+    /// Lowercase character, or the name of a named key such as `tab` or `arrowup`.
+    key: String,
+}
+
+impl KeyCombo {
+    pub fn matches(&self, key: &Key, modifiers: Modifiers) -> bool {
+        self.ctrl == modifiers.command()
+            && self.alt == modifiers.alt()
+            && self.shift == modifiers.shift()
+            && key_name(key).is_some_and(|name| name == self.key)
+    }
+
+    /// Plain keys are left to text inputs while the user is typing.
+    pub fn has_modifier(&self) -> bool {
+        self.ctrl || self.alt
+    }
+}
+
+impl FromStr for KeyCombo {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut combo = KeyCombo {
+            ctrl: false,
+            shift: false,
+            alt: false,
+            key: String::new(),
+        };
+        // Split on `+` but keep a trailing `+` as the key itself, as in `Ctrl++`.
+        let (modifiers, key) = match s.trim().strip_suffix("++") {
+            Some(rest) => (rest, "+"),
+            None => s.trim().rsplit_once('+').unwrap_or(("", s.trim())),
+        };
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            match modifier.trim().to_lowercase().as_str() {
+                "ctrl" | "control" | "cmd" | "command" => combo.ctrl = true,
+                "shift" => combo.shift = true,
+                "alt" | "option" => combo.alt = true,
+                other => {
+                    return Err(tr_args(
+                        "shortcuts-unknown-modifier",
+                        &[("modifier", other.into())],
+                    ))
+                }
+            }
+        }
+        combo.key = match key.trim().to_lowercase().as_str() {
+            "" => return Err(tr("shortcuts-missing-key")),
+            "esc" => "escape".to_string(),
+            "up" | "down" | "left" | "right" => format!("arrow{}", key.trim().to_lowercase()),
+            "return" => "enter".to_string(),
+            "plus" => "+".to_string(),
+            key => key.to_string(),
+        };
+        Ok(combo)
+    }
+}
+
+impl std::fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+        let mut chars = self.key.chars();
+        match chars.next() {
+            Some(first) => write!(f, "{}{}", first.to_uppercase(), chars.as_str()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The name `KeyCombo` uses for a pressed key.
+fn key_name(key: &Key) -> Option<String> {
+    match key {
+        Key::Character(c) => Some(c.to_lowercase()),
+        Key::Named(named) => Some(format!("{:?}", named).to_lowercase()),
+        Key::Unidentified => None,
+    }
+}
+
+/// Shortcut bindings, read from `keymap.json` so users can change them.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(KeyCombo, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: Action::ALL
+                .iter()
+                .filter_map(|action| Some((action.default_keys().parse().ok()?, *action)))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Loads `keymap.json`, writing the defaults there on first run. Actions the
+    /// file leaves out keep their default keys; an empty string unbinds one.
+    /// Returns a message for every entry that could not be read.
+    pub fn load() -> (Self, Vec<String>) {
+        let file: BTreeMap<Action, String> = match fs::read_to_string(KEYMAP_PATH) {
+            Ok(contents) => match serde_json::from_str(&contents) {
+                Ok(file) => file,
+                Err(e) => return (Self::default(), vec![format!("keymap.json: {}", e)]),
+            },
+            Err(_) => {
+                Self::write_defaults();
+                BTreeMap::new()
+            }
+        };
+
+        let mut errors = Vec::new();
+        let mut bindings = Vec::new();
+        for action in Action::ALL {
+            let keys = file
+                .get(&action)
+                .map(String::as_str)
+                .unwrap_or(action.default_keys());
+            if keys.trim().is_empty() {
+                continue;
+            }
+            match keys.parse::<KeyCombo>() {
+                Ok(combo) => bindings.push((combo, action)),
+                Err(e) => errors.push(tr_args(
+                    "shortcuts-invalid",
+                    &[
+                        ("keys", keys.into()),
+                        ("action", tr(action.key()).into()),
+                        ("error", e.into()),
+                    ],
+                )),
+            }
+        }
+        (Self { bindings }, errors)
+    }
+
+    fn write_defaults() {
+        let defaults: BTreeMap<Action, &str> = Action::ALL
+            .iter()
+            .map(|action| (*action, action.default_keys()))
+            .collect();
+        let _ = fs::create_dir_all("./settings");
+        if let Err(e) = fs::write(
+            KEYMAP_PATH,
+            serde_json::to_string_pretty(&defaults).unwrap(),
+        ) {
+            tracing::error!(error = %e, "could not write keymap.json");
+        }
+    }
+
+    /// The action bound to a key press. `captured` is set when a widget already
+    /// handled the key, in which case only shortcuts with Ctrl or Alt (and Escape)
+    /// still apply so typing is never hijacked.
+    pub fn action(&self, key: &Key, modifiers: Modifiers, captured: bool) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(combo, _)| {
+                combo.matches(key, modifiers)
+                    && (!captured || combo.has_modifier() || combo.key == "escape")
+            })
+            .map(|(_, action)| *action)
+    }
+
+    /// The keys bound to `action`, for display next to it.
+    pub fn keys(&self, action: Action) -> Option<String> {
+        self.bindings
+            .iter()
+            .find(|(_, a)| *a == action)
+            .map(|(combo, _)| combo.to_string())
+    }
+}
+
+/// Scores how well `query` fuzzy-matches `candidate`: every query character must
+/// appear in order. Consecutive characters and word starts score higher.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let query: Vec<char> = query
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous_match: Option<usize> = None;
+    for q in query {
+        let index = next + candidate[next..].iter().position(|c| *c == q)?;
+        score += 1;
+        if previous_match.is_some_and(|previous| previous + 1 == index) {
+            score += 5;
+        }
+        if index == 0 || !candidate[index - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous_match = Some(index);
+        next = index + 1;
+    }
+    // Prefer shorter candidates when the match is otherwise equal.
+    Some(score * 100 - candidate.len() as i32)
+}
@@ -1,2 +1,12 @@
+pub mod api;
+pub mod appearance;
 pub mod application;
+pub mod backend;
+pub mod cli;
+pub mod diagnostics;
+pub mod i18n;
 pub mod iced_settings;
+pub mod keymap;
+pub mod modelfile;
+pub mod prompts;
+pub mod servers;
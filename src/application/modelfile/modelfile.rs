@@ -0,0 +1,275 @@
+use crate::application::i18n::i18n::{tr, tr_args};
+use serde_json::{json, Map, Value};
+
+/// The instructions Ollama understands in a Modelfile.
+const INSTRUCTIONS: [&str; 7] = [
+    "FROM",
+    "PARAMETER",
+    "TEMPLATE",
+    "SYSTEM",
+    "ADAPTER",
+    "LICENSE",
+    "MESSAGE",
+];
+
+#[derive(Debug, Clone, Copy)]
+enum ParameterKind {
+    Int,
+    Float,
+    Bool,
+    /// May be given multiple times, collected into an array.
+    StringList,
+}
+
+fn parameter_kind(name: &str) -> Option<ParameterKind> {
+    match name {
+        "num_ctx" | "repeat_last_n" | "seed" | "num_predict" | "top_k" | "mirostat" | "num_gpu"
+        | "num_thread" | "num_batch" | "num_keep" | "main_gpu" => Some(ParameterKind::Int),
+        "temperature" | "top_p" | "min_p" | "typical_p" | "repeat_penalty" | "presence_penalty"
+        | "frequency_penalty" | "mirostat_eta" | "mirostat_tau" | "tfs_z" => {
+            Some(ParameterKind::Float)
+        }
+        "use_mmap" | "use_mlock" | "numa" | "penalize_newline" => Some(ParameterKind::Bool),
+        "stop" => Some(ParameterKind::StringList),
+        _ => None,
+    }
+}
+
+/// The parts of a Modelfile the editor works with.
+#[derive(Debug, Clone, Default)]
+pub struct Modelfile {
+    pub from: String,
+    pub system: String,
+    pub template: String,
+    /// `name value` pairs in the order they were written.
+    pub parameters: Vec<(String, String)>,
+}
+
+impl Modelfile {
+    /// Parses Modelfile text, including `"""` delimited multi-line values.
+    /// Instructions the editor does not model are skipped.
+    pub fn parse(text: &str) -> Result<Self, Vec<String>> {
+        let mut modelfile = Modelfile::default();
+        let mut errors = Vec::new();
+        let mut lines = text.lines().enumerate();
+
+        while let Some((number, line)) = lines.next() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (instruction, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let instruction = instruction.to_uppercase();
+            let mut value = rest.trim().to_string();
+
+            if let Some(stripped) = value.strip_prefix("\"\"\"") {
+                if let Some(end) = stripped.find("\"\"\"") {
+                    value = stripped[..end].to_string();
+                } else {
+                    let mut block = stripped.to_string();
+                    let mut closed = false;
+                    for (_, next) in lines.by_ref() {
+                        if let Some(end) = next.find("\"\"\"") {
+                            block.push('\n');
+                            block.push_str(&next[..end]);
+                            closed = true;
+                            break;
+                        }
+                        block.push('\n');
+                        block.push_str(next);
+                    }
+                    if !closed {
+                        errors.push(tr_args(
+                            "modelfile-unterminated-block",
+                            &[("line", (number + 1).into())],
+                        ));
+                    }
+                    value = block.trim_start_matches('\n').to_string();
+                }
+            } else {
+                value = unquote(&value).to_string();
+            }
+
+            match instruction.as_str() {
+                "FROM" => modelfile.from = value,
+                "SYSTEM" => modelfile.system = value,
+                "TEMPLATE" => modelfile.template = value,
+                "PARAMETER" => match value.split_once(char::is_whitespace) {
+                    Some((name, v)) => modelfile
+                        .parameters
+                        .push((name.to_string(), unquote(v.trim()).to_string())),
+                    None => errors.push(tr_args(
+                        "modelfile-parameter-no-value",
+                        &[("line", (number + 1).into())],
+                    )),
+                },
+                other if INSTRUCTIONS.contains(&other) => {}
+                other => errors.push(tr_args(
+                    "modelfile-unknown-instruction",
+                    &[("line", (number + 1).into()), ("instruction", other.into())],
+                )),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(modelfile)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Renders the Modelfile the way `ollama show --modelfile` would.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("FROM {}\n", self.from);
+        for (name, value) in &self.parameters {
+            if value.contains(char::is_whitespace) {
+                text.push_str(&format!("PARAMETER {} \"{}\"\n", name, value));
+            } else {
+                text.push_str(&format!("PARAMETER {} {}\n", name, value));
+            }
+        }
+        if !self.template.is_empty() {
+            text.push_str(&format!("TEMPLATE \"\"\"{}\"\"\"\n", self.template));
+        }
+        if !self.system.is_empty() {
+            text.push_str(&format!("SYSTEM \"\"\"{}\"\"\"\n", self.system));
+        }
+        text
+    }
+
+    /// Checks everything Ollama would reject. Returns one message per problem.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.from.trim().is_empty() {
+            errors.push(tr("modelfile-from-required"));
+        }
+        for (name, value) in &self.parameters {
+            if let Err(e) = parameter_value(name, value) {
+                errors.push(tr_args(
+                    "modelfile-parameter-invalid",
+                    &[("name", name.as_str().into()), ("error", e.into())],
+                ));
+            }
+        }
+        if let Err(e) = validate_template(&self.template) {
+            errors.push(tr_args(
+                "modelfile-template-invalid",
+                &[("error", e.into())],
+            ));
+        }
+        if self.template.contains("\"\"\"") || self.system.contains("\"\"\"") {
+            errors.push(tr("modelfile-triple-quotes"));
+        }
+        errors
+    }
+
+    /// Parameters as the JSON object `/api/create` expects.
+    pub fn parameters_json(&self) -> Value {
+        let mut map = Map::new();
+        for (name, value) in &self.parameters {
+            let Ok(value) = parameter_value(name, value) else {
+                continue;
+            };
+            if let Some(ParameterKind::StringList) = parameter_kind(name) {
+                match map.entry(name.clone()).or_insert_with(|| json!([])) {
+                    Value::Array(items) => items.push(value),
+                    other => *other = json!([value]),
+                }
+            } else {
+                map.insert(name.clone(), value);
+            }
+        }
+        Value::Object(map)
+    }
+}
+
+/// Parses the editor's parameter block, one `name value` pair per line.
+pub fn parse_parameters(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match line.split_once(char::is_whitespace) {
+            Some((name, value)) => (name.to_string(), unquote(value.trim()).to_string()),
+            None => (line.to_string(), String::new()),
+        })
+        .collect()
+}
+
+pub fn format_parameters(parameters: &[(String, String)]) -> String {
+    parameters
+        .iter()
+        .map(|(name, value)| format!("{} {}", name, value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Converts `value` to the JSON type `name` takes. Parameters newer than this
+/// list are passed on as whatever their value looks like, for Ollama to check.
+fn parameter_value(name: &str, value: &str) -> Result<Value, String> {
+    if value.is_empty() {
+        return Err(tr("modelfile-missing-value"));
+    }
+    let Some(kind) = parameter_kind(name) else {
+        return Ok(value
+            .parse::<i64>()
+            .map(Value::from)
+            .or_else(|_| value.parse::<f64>().map(Value::from))
+            .or_else(|_| value.parse::<bool>().map(Value::from))
+            .unwrap_or_else(|_| Value::from(value)));
+    };
+    match kind {
+        ParameterKind::Int => value
+            .parse::<i64>()
+            .map(Value::from)
+            .map_err(|_| tr_args("modelfile-expected-integer", &[("value", value.into())])),
+        ParameterKind::Float => value
+            .parse::<f64>()
+            .map(Value::from)
+            .map_err(|_| tr_args("modelfile-expected-number", &[("value", value.into())])),
+        ParameterKind::Bool => value
+            .parse::<bool>()
+            .map(Value::from)
+            .map_err(|_| tr_args("modelfile-expected-bool", &[("value", value.into())])),
+        ParameterKind::StringList => Ok(Value::from(value)),
+    }
+}
+
+/// Checks that Go template actions are balanced and every block is closed.
+fn validate_template(template: &str) -> Result<(), String> {
+    let mut depth = 0i32;
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| tr("modelfile-unclosed-action"))?;
+        let action = after[..end].trim_matches('-').trim();
+        let keyword = action.split_whitespace().next().unwrap_or_default();
+        match keyword {
+            "if" | "range" | "with" | "block" | "define" => depth += 1,
+            "end" => depth -= 1,
+            _ => {}
+        }
+        if depth < 0 {
+            return Err(tr("modelfile-unmatched-end"));
+        }
+        rest = &after[end + 2..];
+    }
+    if rest.contains("}}") {
+        return Err(tr("modelfile-unmatched-close"));
+    }
+    if depth > 0 {
+        return Err(tr_args("modelfile-missing-end", &[("count", depth.into())]));
+    }
+    Ok(())
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,145 @@
+//! Parsing, rendering and checking Modelfiles without a server.
+
+use super::*;
+
+#[test]
+fn parses_instructions_and_multi_line_blocks() {
+    let modelfile = Modelfile::parse(
+        r#"# A comment
+FROM llama3.2
+parameter temperature 0.7
+PARAMETER stop "<|end|>"
+PARAMETER stop "User:"
+SYSTEM """You are terse.
+Answer in one line."""
+TEMPLATE """{{ .Prompt }}"""
+LICENSE MIT
+"#,
+    )
+    .unwrap();
+    assert_eq!(modelfile.from, "llama3.2");
+    assert_eq!(modelfile.system, "You are terse.\nAnswer in one line.");
+    assert_eq!(modelfile.template, "{{ .Prompt }}");
+    assert_eq!(
+        modelfile.parameters,
+        [
+            ("temperature".to_string(), "0.7".to_string()),
+            ("stop".to_string(), "<|end|>".to_string()),
+            ("stop".to_string(), "User:".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn reports_unknown_instructions_and_unterminated_blocks() {
+    let errors =
+        Modelfile::parse("FROM x\nSTOP now\nPARAMETER seed\nSYSTEM \"\"\"open").unwrap_err();
+    assert_eq!(
+        errors,
+        [
+            "Line 2: unknown instruction STOP",
+            "Line 3: PARAMETER needs a value",
+            "Line 4: unterminated \"\"\" block",
+        ]
+    );
+}
+
+#[test]
+fn round_trips_through_text() {
+    let modelfile = Modelfile {
+        from: "llama3.2".to_string(),
+        system: "Line one\nLine two".to_string(),
+        template: "{{ if .System }}{{ .System }}{{ end }}".to_string(),
+        parameters: vec![
+            ("num_ctx".to_string(), "4096".to_string()),
+            ("stop".to_string(), "end of turn".to_string()),
+        ],
+    };
+    let parsed = Modelfile::parse(&modelfile.to_text()).unwrap();
+    assert_eq!(parsed.from, modelfile.from);
+    assert_eq!(parsed.system, modelfile.system);
+    assert_eq!(parsed.template, modelfile.template);
+    assert_eq!(parsed.parameters, modelfile.parameters);
+}
+
+#[test]
+fn validates_parameters_and_templates() {
+    let modelfile = Modelfile {
+        from: " ".to_string(),
+        template: "{{ if .System }}{{ .System }}".to_string(),
+        parameters: vec![
+            ("num_ctx".to_string(), "lots".to_string()),
+            ("use_mmap".to_string(), "yes".to_string()),
+            ("temperature".to_string(), String::new()),
+        ],
+        ..Modelfile::default()
+    };
+    assert_eq!(
+        modelfile.validate(),
+        [
+            "FROM: a base model is required",
+            "PARAMETER num_ctx: expected an integer, got lots",
+            "PARAMETER use_mmap: expected true or false, got yes",
+            "PARAMETER temperature: missing value",
+            "TEMPLATE: 1 block(s) missing {{ end }}",
+        ]
+    );
+    assert_eq!(
+        validate_template("{{ end }}"),
+        Err("{{ end }} without a matching block".to_string())
+    );
+    assert_eq!(
+        validate_template("{{ .Prompt"),
+        Err("unclosed {{".to_string())
+    );
+    assert_eq!(
+        validate_template("}}"),
+        Err("}} without a matching {{".to_string())
+    );
+}
+
+#[test]
+fn passes_unknown_parameters_through() {
+    let modelfile = Modelfile {
+        from: "llama3.2".to_string(),
+        parameters: vec![
+            ("temperature".to_string(), "1".to_string()),
+            ("num_future".to_string(), "8".to_string()),
+            ("future_ratio".to_string(), "0.5".to_string()),
+            ("future_flag".to_string(), "true".to_string()),
+            ("future_name".to_string(), "fast".to_string()),
+            ("stop".to_string(), "a".to_string()),
+            ("stop".to_string(), "b".to_string()),
+        ],
+        ..Modelfile::default()
+    };
+    assert!(modelfile.validate().is_empty());
+    assert_eq!(
+        modelfile.parameters_json(),
+        json!({
+            "temperature": 1.0,
+            "num_future": 8,
+            "future_ratio": 0.5,
+            "future_flag": true,
+            "future_name": "fast",
+            "stop": ["a", "b"],
+        })
+    );
+}
+
+#[test]
+fn parses_the_editor_parameter_block() {
+    let parameters = parse_parameters("temperature 0.2\n\n# note\nstop \"a b\"\nseed");
+    assert_eq!(
+        parameters,
+        [
+            ("temperature".to_string(), "0.2".to_string()),
+            ("stop".to_string(), "a b".to_string()),
+            ("seed".to_string(), String::new()),
+        ]
+    );
+    assert_eq!(
+        format_parameters(&parameters[..2]),
+        "temperature 0.2\nstop a b"
+    );
+}
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+const LIBRARY_PATH: &str = "./settings/prompts.json";
+
+/// A reusable prompt. `{{name}}` placeholders in `body` are asked for before sending.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub name: String,
+    /// Typed as `/command` in the prompt input.
+    #[serde(default)]
+    pub command: String,
+    pub body: String,
+}
+
+impl PromptTemplate {
+    /// Placeholder names in order of first appearance.
+    pub fn variables(&self) -> Vec<String> {
+        let mut variables: Vec<String> = Vec::new();
+        let mut rest = self.body.as_str();
+        while let Some(start) = rest.find("{{") {
+            let after = &rest[start + 2..];
+            let Some(end) = after.find("}}") else {
+                break;
+            };
+            let name = after[..end].trim();
+            if !name.is_empty() && !variables.iter().any(|v| v == name) {
+                variables.push(name.to_string());
+            }
+            rest = &after[end + 2..];
+        }
+        variables
+    }
+
+    /// The body with every `{{name}}` replaced by its value.
+    pub fn fill(&self, values: &HashMap<String, String>) -> String {
+        let mut text = String::new();
+        let mut rest = self.body.as_str();
+        while let Some(start) = rest.find("{{") {
+            let after = &rest[start + 2..];
+            let Some(end) = after.find("}}") else {
+                break;
+            };
+            text.push_str(&rest[..start]);
+            match values.get(after[..end].trim()) {
+                Some(value) => text.push_str(value),
+                None => text.push_str(&rest[start..start + end + 4]),
+            }
+            rest = &after[end + 2..];
+        }
+        text.push_str(rest);
+        text
+    }
+}
+
+impl std::fmt::Display for PromptTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromptLibrary {
+    pub templates: Vec<PromptTemplate>,
+}
+
+impl PromptLibrary {
+    /// Loads `prompts.json`, or a few starter templates on first run.
+    pub fn load() -> Self {
+        fs::read_to_string(LIBRARY_PATH)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_else(Self::starter)
+    }
+
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub fn save(&self) {
+        let _ = fs::create_dir_all("./settings");
+        if let Err(e) = fs::write(LIBRARY_PATH, serde_json::to_string_pretty(self).unwrap()) {
+            tracing::error!(error = %e, "could not save prompts.json");
+        }
+    }
+
+    fn starter() -> Self {
+        let template = |name: &str, command: &str, body: &str| PromptTemplate {
+            name: name.to_string(),
+            command: command.to_string(),
+            body: body.to_string(),
+        };
+        Self {
+            templates: vec![
+                template(
+                    "Review this diff",
+                    "review",
+                    "Review the following diff. Point out bugs, risky changes and missing tests.\n\n{{diff}}",
+                ),
+                template(
+                    "Translate",
+                    "translate",
+                    "Translate the following text to {{language}}. Reply with the translation only.\n\n{{text}}",
+                ),
+                template(
+                    "Summarize",
+                    "summarize",
+                    "Summarize the following text in a few bullet points.\n\n{{text}}",
+                ),
+            ],
+        }
+    }
+
+    pub fn find_command(&self, command: &str) -> Option<&PromptTemplate> {
+        self.templates
+            .iter()
+            .find(|t| !t.command.is_empty() && t.command.eq_ignore_ascii_case(command))
+    }
+
+    /// Adds `template`, replacing any template with the same name.
+    pub fn upsert(&mut self, template: PromptTemplate) {
+        match self.templates.iter_mut().find(|t| t.name == template.name) {
+            Some(existing) => *existing = template,
+            None => self.templates.push(template),
+        }
+    }
+
+    /// Merges the library at `path` into this one. Returns how many templates were read.
+    pub fn import(&mut self, path: &str) -> Result<usize, String> {
+        let contents = fs::read_to_string(path.trim()).map_err(|e| e.to_string())?;
+        let imported: PromptLibrary = serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+        let count = imported.templates.len();
+        for template in imported.templates {
+            self.upsert(template);
+        }
+        Ok(count)
+    }
+
+    pub fn export(&self, path: &str) -> Result<(), String> {
+        fs::write(path.trim(), serde_json::to_string_pretty(self).unwrap())
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,349 @@
+use crate::application::backend::backend::{BackendKind, ChatBackend};
+use crate::application::i18n::i18n::{tr, tr_args};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use uuid::Uuid;
+
+const SECRETS_PATH: &str = "./settings/secrets.json";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AuthKind {
+    #[default]
+    None,
+    Bearer,
+    Basic,
+}
+
+impl AuthKind {
+    pub const ALL: [AuthKind; 3] = [AuthKind::None, AuthKind::Bearer, AuthKind::Basic];
+}
+
+impl std::fmt::Display for AuthKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuthKind::None => write!(f, "{}", tr("auth-none")),
+            AuthKind::Bearer => write!(f, "{}", tr("auth-bearer")),
+            AuthKind::Basic => write!(f, "{}", tr("auth-basic")),
+        }
+    }
+}
+
+/// Credentials for a server. These are kept in `secrets.json`, never in `settings.json`.
+#[derive(Debug, Clone, Default, PartialEq, Hash, Serialize, Deserialize)]
+pub struct ServerSecrets {
+    #[serde(default)]
+    pub token: String,
+    #[serde(default)]
+    pub password: String,
+    /// Extra `name: value` headers sent with every request.
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+}
+
+/// A named Ollama server and how to connect to it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerProfile {
+    pub id: Uuid,
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub backend: BackendKind,
+    #[serde(default)]
+    pub auth: AuthKind,
+    /// User name for basic auth; the password lives in `secrets`.
+    #[serde(default)]
+    pub username: String,
+    /// PEM file with extra CA certificates to trust.
+    #[serde(default)]
+    pub ca_cert: Option<PathBuf>,
+    /// PEM file with a client certificate and its private key.
+    #[serde(default)]
+    pub client_identity: Option<PathBuf>,
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+    /// Connect and read timeout in seconds.
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    #[serde(skip)]
+    pub secrets: ServerSecrets,
+}
+
+impl ServerProfile {
+    pub fn new(name: &str, url: &str) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: name.to_string(),
+            url: url.to_string(),
+            backend: BackendKind::Ollama,
+            auth: AuthKind::None,
+            username: String::new(),
+            ca_cert: None,
+            client_identity: None,
+            accept_invalid_certs: false,
+            timeout_secs: None,
+            secrets: ServerSecrets::default(),
+        }
+    }
+
+    pub fn backend(&self) -> &'static dyn ChatBackend {
+        self.backend.backend()
+    }
+
+    /// `url` joined with an API path such as `/api/tags`.
+    pub fn endpoint(&self, path: &str) -> String {
+        format!("{}{}", self.url.trim_end_matches('/'), path)
+    }
+
+    /// Changes whenever a setting `client` builds the connection from changes, so
+    /// work keyed by it restarts with the new credentials.
+    pub fn connection_fingerprint(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.auth.hash(&mut hasher);
+        self.username.hash(&mut hasher);
+        self.secrets.hash(&mut hasher);
+        self.ca_cert.hash(&mut hasher);
+        self.client_identity.hash(&mut hasher);
+        self.accept_invalid_certs.hash(&mut hasher);
+        self.timeout_secs.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn headers(&self) -> Result<HeaderMap, String> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.secrets.headers {
+            let name = HeaderName::from_bytes(name.trim().as_bytes()).map_err(|_| {
+                tr_args(
+                    "server-header-name-invalid",
+                    &[("name", name.as_str().into())],
+                )
+            })?;
+            let value = HeaderValue::from_str(value.trim()).map_err(|_| {
+                tr_args(
+                    "server-header-value-invalid",
+                    &[("name", name.as_str().into())],
+                )
+            })?;
+            headers.insert(name, value);
+        }
+        let authorization = match self.auth {
+            AuthKind::None => None,
+            AuthKind::Bearer => Some(format!("Bearer {}", self.secrets.token)),
+            AuthKind::Basic => Some(format!(
+                "Basic {}",
+                base64(format!("{}:{}", self.username, self.secrets.password).as_bytes())
+            )),
+        };
+        if let Some(authorization) = authorization {
+            let mut value = HeaderValue::from_str(&authorization)
+                .map_err(|_| tr("server-credentials-invalid"))?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+        Ok(headers)
+    }
+
+    fn certificates(&self) -> Result<Vec<reqwest::Certificate>, String> {
+        match &self.ca_cert {
+            Some(path) => fs::read(path)
+                .map_err(|e| unreadable(path, e))
+                .and_then(|pem| {
+                    reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| {
+                        tr_args("server-ca-cert-invalid", &[("error", e.to_string().into())])
+                    })
+                }),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn identity(&self) -> Result<Option<reqwest::Identity>, String> {
+        match &self.client_identity {
+            Some(path) => fs::read(path)
+                .map_err(|e| unreadable(path, e))
+                .and_then(|pem| {
+                    reqwest::Identity::from_pem(&pem).map(Some).map_err(|e| {
+                        tr_args(
+                            "server-client-cert-invalid",
+                            &[("error", e.to_string().into())],
+                        )
+                    })
+                }),
+            None => Ok(None),
+        }
+    }
+
+    /// Client for streaming requests. The timeout applies to connecting and to
+    /// each read, so long generations are not cut off.
+    pub fn client(&self) -> Result<reqwest::Client, String> {
+        let mut builder = reqwest::Client::builder()
+            .default_headers(self.headers()?)
+            .danger_accept_invalid_certs(self.accept_invalid_certs);
+        for certificate in self.certificates()? {
+            builder = builder.add_root_certificate(certificate);
+        }
+        if let Some(identity) = self.identity()? {
+            builder = builder.identity(identity);
+        }
+        if let Some(secs) = self.timeout_secs {
+            builder = builder
+                .connect_timeout(Duration::from_secs(secs))
+                .read_timeout(Duration::from_secs(secs));
+        }
+        builder.build().map_err(|e| e.to_string())
+    }
+}
+
+impl std::fmt::Display for ServerProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Checks that `url` is an http(s) address with a host and returns it without a
+/// trailing slash, the form `endpoint` expects.
+pub fn normalize_url(url: &str) -> Result<String, String> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err(tr("server-url-empty"));
+    }
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        return Err(tr("server-url-scheme"));
+    }
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| tr_args("server-url-invalid", &[("error", e.to_string().into())]))?;
+    if parsed.host_str().is_none_or(str::is_empty) {
+        return Err(tr("server-url-no-host"));
+    }
+    if parsed.query().is_some() || parsed.fragment().is_some() {
+        return Err(tr("server-url-query"));
+    }
+    // `Url` drops default ports and lowercases the host, so rebuild from the parsed form.
+    Ok(parsed.as_str().trim_end_matches('/').to_string())
+}
+
+/// Fills in each profile's secrets from `secrets.json`.
+pub fn load_secrets(servers: &mut [ServerProfile]) {
+    let secrets: HashMap<Uuid, ServerSecrets> = fs::read_to_string(SECRETS_PATH)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default();
+    for server in servers {
+        if let Some(secrets) = secrets.get(&server.id) {
+            server.secrets = secrets.clone();
+        }
+    }
+}
+
+/// Writes every profile's secrets to `secrets.json`, readable only by the current user.
+pub fn save_secrets(servers: &[ServerProfile]) -> std::io::Result<()> {
+    let secrets: HashMap<Uuid, &ServerSecrets> = servers
+        .iter()
+        .filter(|server| server.secrets != ServerSecrets::default())
+        .map(|server| (server.id, &server.secrets))
+        .collect();
+    let json = serde_json::to_string_pretty(&secrets)?;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(SECRETS_PATH)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    std::io::Write::write_all(&mut file, json.as_bytes())
+}
+
+/// Parses one `Name: value` header per line.
+pub fn parse_headers(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_string(), value.trim().to_string()))
+        .filter(|(name, _)| !name.is_empty())
+        .collect()
+}
+
+pub fn format_headers(headers: &[(String, String)]) -> String {
+    headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Standard base64 with padding, for the basic auth header.
+/// Why a certificate file named in the profile could not be read.
+fn unreadable(path: &Path, error: std::io::Error) -> String {
+    tr_args(
+        "server-file-unreadable",
+        &[
+            ("path", path.display().to_string().into()),
+            ("error", error.to_string().into()),
+        ],
+    )
+}
+
+fn base64(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+/// Result of polling a server's `/api/version`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum ServerHealth {
+    /// No check has finished yet.
+    #[default]
+    Unknown,
+    Connected {
+        version: String,
+    },
+    Disconnected {
+        error: String,
+        failures: u32,
+    },
+}
+
+impl ServerHealth {
+    /// Only a failed check counts as offline, so nothing is blocked before the first poll.
+    pub fn is_offline(&self) -> bool {
+        matches!(self, ServerHealth::Disconnected { .. })
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            ServerHealth::Unknown => tr("health-checking"),
+            ServerHealth::Connected { version } => {
+                tr_args("health-connected", &[("version", version.as_str().into())])
+            }
+            ServerHealth::Disconnected { .. } => tr("health-disconnected"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
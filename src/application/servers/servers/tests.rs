@@ -0,0 +1,37 @@
+//! Checking and normalizing server addresses.
+
+use super::*;
+
+#[test]
+fn normalizes_addresses() {
+    assert_eq!(
+        normalize_url(" http://localhost:11434/ ").unwrap(),
+        "http://localhost:11434"
+    );
+    assert_eq!(
+        normalize_url("http://Example.com:80/").unwrap(),
+        "http://example.com"
+    );
+    assert_eq!(
+        normalize_url("https://gpu.lan:443/ollama/").unwrap(),
+        "https://gpu.lan/ollama"
+    );
+}
+
+#[test]
+fn rejects_addresses_it_cannot_use() {
+    assert_eq!(normalize_url("  "), Err(tr("server-url-empty")));
+    assert_eq!(
+        normalize_url("localhost:11434"),
+        Err(tr("server-url-scheme"))
+    );
+    assert_eq!(
+        normalize_url("http://localhost:11434/?model=llama3"),
+        Err(tr("server-url-query"))
+    );
+    assert_eq!(
+        normalize_url("http://localhost:11434/#chat"),
+        Err(tr("server-url-query"))
+    );
+    assert!(normalize_url("http://local host").is_err());
+}
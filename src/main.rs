@@ -2,13 +2,33 @@
 
 mod application;
 use application::application::*;
+use application::cli::cli;
+use application::diagnostics::diagnostics;
 use application::iced_settings::iced_settings::*;
 
 pub fn main() -> iced::Result {
+    // Subcommands run headless. Release builds on Windows have no console of
+    // their own, so their output is only visible when redirected.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match cli::run(&args) {
+        cli::Launch::Window(options) => options,
+        cli::Launch::Exit(code) => std::process::exit(code),
+    };
+
+    // Kept alive so buffered log lines are written out when the window closes.
+    let _log_guard = diagnostics::init();
+
+    let saved = OllamaGUI::load_settings();
+    let window = windows_settings(options.window_size, saved.window());
     iced::application("Ollama GUI", OllamaGUI::update, OllamaGUI::view)
         .subscription(OllamaGUI::subscription)
-        .settings(settings())
-        .window(windows_settings())
+        .settings(settings(saved.fonts()))
+        .window(window)
         .theme(OllamaGUI::theme)
-        .run()
+        .scale_factor(OllamaGUI::scale_factor)
+        .run_with(move || {
+            let gui = OllamaGUI::with_options(&options);
+            let restore = gui.restore_window();
+            (gui, restore)
+        })
 }